@@ -1,3 +1,69 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, BitAnd, BitOr, Sub};
+use std::str::FromStr;
+
+/// One of the cube colours that can be drawn from the bag.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Colour {
+    Blue,
+    Green,
+    Red,
+}
+
+impl Colour {
+    pub const ALL: [Colour; 3] = [Colour::Blue, Colour::Green, Colour::Red];
+
+    /// Returns the position of this colour in [`Colour::ALL`].
+    pub fn index(&self) -> usize {
+        match self {
+            Colour::Blue => 0,
+            Colour::Green => 1,
+            Colour::Red => 2,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Colour::Blue => "blue",
+            Colour::Green => "green",
+            Colour::Red => "red",
+        }
+    }
+}
+
+impl FromStr for Colour {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Colour::ALL
+            .into_iter()
+            .find(|colour| colour.name() == s)
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A calculation whose result is too large to represent.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OverflowError {
+    /// What was being calculated, e.g. `power`.
+    pub operation: &'static str,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the {} is too large to calculate", self.operation)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct ColourSet {
     pub num_blue: u32,
     pub num_green: u32,
@@ -13,39 +79,172 @@ impl ColourSet {
         }
     }
 
+    /// Returns a [`ColourSet`] holding only the given number of cubes of one colour.
+    pub fn single(colour: Colour, num: u32) -> ColourSet {
+        match colour {
+            Colour::Blue => ColourSet::new(num, 0, 0),
+            Colour::Green => ColourSet::new(0, num, 0),
+            Colour::Red => ColourSet::new(0, 0, num),
+        }
+    }
+
+    /// Parses a [`ColourSet`] from a description string, keeping the last count of any repeated colour.
+    /// Example: `1 red, 2 green, 6 blue`
+    /// Only tests use this, as everything else goes through [`super::GameParser::parse_subset`].
+    ///
+    /// # Panics
+    /// Panics if the description is invalid.
+    #[cfg(test)]
     pub fn parse_from_description(set_description: &str) -> ColourSet {
-        let parts = set_description.split(", ");
-
-        let mut num_blue = 0;
-        let mut num_green = 0;
-        let mut num_red = 0;
-
-        for part in parts {
-            let mut part = part.split(' ');
-            let num = part.next().unwrap().parse::<u32>().unwrap();
-            let color = part.next().unwrap();
-
-            match color {
-                "blue" => {
-                    num_blue = num;
-                }
-                "green" => {
-                    num_green = num;
-                }
-                "red" => {
-                    num_red = num;
-                }
-                _ => {}
-            }
+        super::GameParser::default()
+            .parse_subset(set_description)
+            .unwrap_or_else(|err| panic!("invalid subset '{}': {}", set_description, err))
+    }
+
+    /// Returns the number of cubes of the given [`Colour`] in this [`ColourSet`].
+    pub fn count(&self, colour: Colour) -> u32 {
+        match colour {
+            Colour::Blue => self.num_blue,
+            Colour::Green => self.num_green,
+            Colour::Red => self.num_red,
         }
+    }
+
+    /// Returns the join of the two sets: the smallest set that contains both of them.
+    pub fn join(&self, other: &ColourSet) -> ColourSet {
+        ColourSet::new(
+            self.num_blue.max(other.num_blue),
+            self.num_green.max(other.num_green),
+            self.num_red.max(other.num_red),
+        )
+    }
+
+    /// Returns the meet of the two sets: the largest set contained in both of them.
+    pub fn meet(&self, other: &ColourSet) -> ColourSet {
+        ColourSet::new(
+            self.num_blue.min(other.num_blue),
+            self.num_green.min(other.num_green),
+            self.num_red.min(other.num_red),
+        )
+    }
+
+    /// Adds the two sets colour by colour, returning `None` on overflow.
+    pub fn checked_add(&self, other: &ColourSet) -> Option<ColourSet> {
+        Some(ColourSet::new(
+            self.num_blue.checked_add(other.num_blue)?,
+            self.num_green.checked_add(other.num_green)?,
+            self.num_red.checked_add(other.num_red)?,
+        ))
+    }
 
-        ColourSet::new(num_blue, num_green, num_red)
+    /// Subtracts `other` colour by colour, returning `None` if any colour would go below zero.
+    pub fn checked_sub(&self, other: &ColourSet) -> Option<ColourSet> {
+        Some(ColourSet::new(
+            self.num_blue.checked_sub(other.num_blue)?,
+            self.num_green.checked_sub(other.num_green)?,
+            self.num_red.checked_sub(other.num_red)?,
+        ))
     }
 
     /// Returns the power of this [`ColourSet`].
     /// The power is the product of the number of blue, green and red cubes.
-    pub fn power(&self) -> u32 {
-        self.num_blue * self.num_green * self.num_red
+    /// It is calculated in 64 bits, returning an error if even that overflows.
+    pub fn power(&self) -> Result<u64, OverflowError> {
+        (self.num_blue as u64)
+            .checked_mul(self.num_green as u64)
+            .and_then(|power| power.checked_mul(self.num_red as u64))
+            .ok_or(OverflowError { operation: "power" })
+    }
+}
+
+/// Orders sets by dominance: a set is less than or equal to another
+/// if it has no more cubes of any colour. Sets that each have more
+/// of some colour than the other are incomparable.
+impl PartialOrd for ColourSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let orderings = Colour::ALL.map(|colour| self.count(colour).cmp(&other.count(colour)));
+
+        if orderings.iter().all(|ordering| ordering.is_eq()) {
+            Some(Ordering::Equal)
+        } else if orderings.iter().all(|ordering| ordering.is_le()) {
+            Some(Ordering::Less)
+        } else if orderings.iter().all(|ordering| ordering.is_ge()) {
+            Some(Ordering::Greater)
+        } else {
+            None
+        }
+    }
+}
+
+/// The union of two sets, see [`ColourSet::join`].
+impl BitOr for ColourSet {
+    type Output = ColourSet;
+
+    fn bitor(self, other: ColourSet) -> ColourSet {
+        self.join(&other)
+    }
+}
+
+/// The intersection of two sets, see [`ColourSet::meet`].
+impl BitAnd for ColourSet {
+    type Output = ColourSet;
+
+    fn bitand(self, other: ColourSet) -> ColourSet {
+        self.meet(&other)
+    }
+}
+
+impl Add for ColourSet {
+    type Output = ColourSet;
+
+    fn add(self, other: ColourSet) -> ColourSet {
+        self.checked_add(&other)
+            .expect("attempt to add colour sets with overflow")
+    }
+}
+
+impl Sub for ColourSet {
+    type Output = ColourSet;
+
+    /// # Panics
+    /// Panics if `other` has more cubes of any colour than `self`, use [`ColourSet::checked_sub`] to handle this.
+    fn sub(self, other: ColourSet) -> ColourSet {
+        self.checked_sub(&other)
+            .expect("attempt to subtract colour sets with underflow")
+    }
+}
+
+impl Sum for ColourSet {
+    fn sum<I: Iterator<Item = ColourSet>>(iter: I) -> ColourSet {
+        iter.fold(ColourSet::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a ColourSet> for ColourSet {
+    fn sum<I: Iterator<Item = &'a ColourSet>>(iter: I) -> ColourSet {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for ColourSet {
+    /// Writes the canonical description of this [`ColourSet`], e.g. `3 blue, 4 red`.
+    /// Colours are written in blue, green, red order and empty colours are omitted,
+    /// unless every colour is empty, in which case all three are written as zero.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let is_empty = Colour::ALL.iter().all(|colour| self.count(*colour) == 0);
+
+        let mut separator = "";
+        for colour in Colour::ALL {
+            let num = self.count(colour);
+            if num == 0 && !is_empty {
+                continue;
+            }
+
+            write!(f, "{}{} {}", separator, num, colour)?;
+            separator = ", ";
+        }
+
+        Ok(())
     }
 }
 
@@ -71,9 +270,143 @@ mod colour_set_tests {
         assert_eq!(set.num_red, 0);
     }
 
+    #[test]
+    fn test_display() {
+        assert_eq!(ColourSet::new(3, 0, 4).to_string(), "3 blue, 4 red");
+        assert_eq!(
+            ColourSet::new(6, 2, 1).to_string(),
+            "6 blue, 2 green, 1 red"
+        );
+        assert_eq!(ColourSet::new(0, 2, 0).to_string(), "2 green");
+        assert_eq!(
+            ColourSet::new(0, 0, 0).to_string(),
+            "0 blue, 0 green, 0 red"
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        for description in [
+            "4 red, 3 blue",
+            "1 red, 2 green, 6 blue",
+            "2 green",
+            "0 red",
+        ] {
+            let set = ColourSet::parse_from_description(description);
+            let reparsed = ColourSet::parse_from_description(&set.to_string());
+            assert_eq!(reparsed, set);
+        }
+    }
+
+    #[test]
+    fn test_single() {
+        assert_eq!(ColourSet::single(Colour::Blue, 3), ColourSet::new(3, 0, 0));
+        assert_eq!(ColourSet::single(Colour::Green, 4), ColourSet::new(0, 4, 0));
+        assert_eq!(ColourSet::single(Colour::Red, 5), ColourSet::new(0, 0, 5));
+    }
+
+    #[test]
+    fn test_count() {
+        let set = ColourSet::new(3, 4, 5);
+        assert_eq!(set.count(Colour::Blue), 3);
+        assert_eq!(set.count(Colour::Green), 4);
+        assert_eq!(set.count(Colour::Red), 5);
+    }
+
+    #[test]
+    fn test_partial_order() {
+        let small = ColourSet::new(1, 2, 3);
+        let large = ColourSet::new(4, 5, 6);
+        let mixed = ColourSet::new(4, 0, 3);
+
+        assert!(small <= small);
+        assert!(small < large);
+        assert!(large > small);
+        assert!(mixed < large);
+        assert_eq!(small.partial_cmp(&small), Some(Ordering::Equal));
+        assert_eq!(small.partial_cmp(&mixed), None);
+        assert_eq!(mixed.partial_cmp(&small), None);
+    }
+
+    #[test]
+    fn test_join_and_meet() {
+        let a = ColourSet::new(3, 0, 4);
+        let b = ColourSet::new(6, 2, 1);
+
+        assert_eq!(a | b, ColourSet::new(6, 2, 4));
+        assert_eq!(a & b, ColourSet::new(3, 0, 1));
+
+        // The join is the least upper bound and the meet the greatest lower bound
+        assert!(a <= (a | b) && b <= (a | b));
+        assert!((a & b) <= a && (a & b) <= b);
+
+        // Absorption laws
+        assert_eq!(a | (a & b), a);
+        assert_eq!(a & (a | b), a);
+
+        // The empty set is the identity of join
+        assert_eq!(a | ColourSet::default(), a);
+    }
+
+    #[test]
+    fn test_add_and_sub() {
+        let a = ColourSet::new(3, 0, 4);
+        let b = ColourSet::new(6, 2, 1);
+
+        assert_eq!(a + b, ColourSet::new(9, 2, 5));
+        assert_eq!((a + b) - b, a);
+        assert_eq!(b.checked_sub(&a), None);
+        assert_eq!(
+            a.checked_sub(&ColourSet::new(3, 0, 0)),
+            Some(ColourSet::new(0, 0, 4))
+        );
+        assert_eq!(ColourSet::new(u32::MAX, 0, 0).checked_add(&a), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "underflow")]
+    fn test_sub_underflow() {
+        let _ = ColourSet::new(3, 0, 4) - ColourSet::new(0, 1, 0);
+    }
+
+    #[test]
+    fn test_sum() {
+        let sets = [
+            ColourSet::new(3, 0, 4),
+            ColourSet::new(6, 2, 1),
+            ColourSet::new(0, 2, 0),
+        ];
+
+        assert_eq!(sets.iter().sum::<ColourSet>(), ColourSet::new(9, 4, 5));
+        assert_eq!(sets.into_iter().sum::<ColourSet>(), ColourSet::new(9, 4, 5));
+        assert_eq!(
+            std::iter::empty::<ColourSet>().sum::<ColourSet>(),
+            ColourSet::default()
+        );
+    }
+
     #[test]
     fn test_power() {
         let set = ColourSet::new(3, 4, 5);
-        assert_eq!(set.power(), 60);
+        assert_eq!(set.power(), Ok(60));
+    }
+
+    #[test]
+    fn test_power_beyond_u32() {
+        let set = ColourSet::new(2000, 3000, 4000);
+        assert_eq!(set.power(), Ok(24_000_000_000));
+
+        let set = ColourSet::new(u32::MAX, u32::MAX, 1);
+        assert_eq!(set.power(), Ok(u32::MAX as u64 * u32::MAX as u64));
+    }
+
+    #[test]
+    fn test_power_overflow() {
+        let set = ColourSet::new(u32::MAX, u32::MAX, 2);
+        assert_eq!(set.power(), Err(OverflowError { operation: "power" }));
+        assert_eq!(
+            set.power().unwrap_err().to_string(),
+            "the power is too large to calculate"
+        );
     }
 }
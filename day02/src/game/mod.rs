@@ -1,6 +1,22 @@
 mod colour_set;
-pub use colour_set::ColourSet;
+mod parser;
+pub use colour_set::{Colour, ColourSet, OverflowError};
+pub use parser::{DuplicatePolicy, GameParser, ParseError, SubsetBuilder};
 
+use std::fmt;
+
+/// A colour in one of a [`Game`]'s subsets that drew more cubes than the bag holds.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Violation {
+    /// The index of the offending subset within [`Game::subsets`].
+    pub subset_index: usize,
+    pub colour: Colour,
+    pub drawn: u32,
+    /// How many more cubes were drawn than the bag holds.
+    pub excess: u32,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Game {
     pub id: u32,
     pub subsets: Vec<ColourSet>,
@@ -18,55 +34,199 @@ impl Game {
         self.subsets.push(subset);
     }
 
-    /// Parses a [`Game`] from a description string.
+    /// Parses a [`Game`] from a description string, keeping the last count of any repeated colour.
     /// Example: `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green`
+    /// Only tests use this, as everything else goes through [`GameParser::parse_game`].
+    ///
+    /// # Panics
+    /// Panics if the description is invalid.
+    #[cfg(test)]
     pub fn parse_from_description(game_description: &str) -> Game {
-        // Split line on the colon
-        let mut parts = game_description.split(": ");
-        let game_id = parts
-            .next()
-            .unwrap()
-            .trim_start_matches("Game ")
-            .parse::<u32>()
-            .unwrap();
-
-        let mut game = Game::new(game_id);
-
-        let subsets = parts.next().unwrap();
-        let subsets = subsets.split("; ").collect::<Vec<_>>();
-
-        for subset in subsets {
-            let subset = ColourSet::parse_from_description(subset);
-            game.add_subset(subset);
-        }
-
-        game
+        GameParser::default()
+            .parse_game(game_description)
+            .unwrap_or_else(|err| panic!("invalid game '{}': {}", game_description, err))
     }
 
     /// Returns `true` if the given [`ColourSet`] is valid for this [`Game`].
     pub fn is_set_valid(&self, set: &ColourSet) -> bool {
-        for subset in &self.subsets {
-            if subset.num_blue > set.num_blue
-                || subset.num_green > set.num_green
-                || subset.num_red > set.num_red
-            {
-                return false;
-            }
-        }
+        self.subsets.iter().all(|subset| subset <= set)
+    }
 
-        true
+    /// Returns every way in which the given [`ColourSet`] is invalid for this [`Game`],
+    /// in subset then colour order. The set is valid if this is empty.
+    pub fn validate(&self, set: &ColourSet) -> Vec<Violation> {
+        self.subsets
+            .iter()
+            .enumerate()
+            .flat_map(|(subset_index, subset)| {
+                Colour::ALL.into_iter().filter_map(move |colour| {
+                    let drawn = subset.count(colour);
+                    let excess = drawn.checked_sub(set.count(colour))?;
+                    (excess > 0).then_some(Violation {
+                        subset_index,
+                        colour,
+                        drawn,
+                        excess,
+                    })
+                })
+            })
+            .collect()
     }
 
     /// Returns the generate minimal set of this [`Game`].
+    /// This is the join of every subset drawn in the game.
     pub fn generate_minimal_set(&self) -> ColourSet {
-        let mut minimal_set = ColourSet::new(0, 0, 0);
+        self.subsets
+            .iter()
+            .fold(ColourSet::default(), |minimal_set, subset| {
+                minimal_set | *subset
+            })
+    }
+}
 
-        for subset in &self.subsets {
-            minimal_set.num_blue = std::cmp::max(minimal_set.num_blue, subset.num_blue);
-            minimal_set.num_green = std::cmp::max(minimal_set.num_green, subset.num_green);
-            minimal_set.num_red = std::cmp::max(minimal_set.num_red, subset.num_red);
+impl fmt::Display for Game {
+    /// Writes the canonical description of this [`Game`], which [`GameParser::parse_game`] reads back.
+    /// Example: `Game 1: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green`, or `Game 2:` with no subsets
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}:", self.id)?;
+
+        for (index, subset) in self.subsets.iter().enumerate() {
+            let separator = if index > 0 { ";" } else { "" };
+            write!(f, "{} {}", separator, subset)?;
         }
 
-        minimal_set
+        Ok(())
+    }
+}
+
+impl fmt::Display for Violation {
+    /// Example: `subset 1: drew 20 red, 8 more than the bag holds`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "subset {}: drew {} {}, {} more than the bag holds",
+            self.subset_index + 1,
+            self.drawn,
+            self.colour,
+            self.excess
+        )
+    }
+}
+
+#[cfg(test)]
+mod game_tests {
+    use super::*;
+    use crate::generator::{self, GeneratorConfig};
+
+    #[test]
+    fn test_display() {
+        let game =
+            Game::parse_from_description("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+        assert_eq!(
+            game.to_string(),
+            "Game 1: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green"
+        );
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let descriptions = [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 42: 0 red",
+        ];
+
+        for description in descriptions {
+            let game = Game::parse_from_description(description);
+            let printed = game.to_string();
+            let reparsed = Game::parse_from_description(&printed);
+
+            assert_eq!(reparsed, game);
+            // Printing is idempotent once the description is canonical
+            assert_eq!(reparsed.to_string(), printed);
+        }
+    }
+
+    #[test]
+    fn test_display_round_trip_without_subsets() {
+        let game = Game::new(7);
+
+        assert_eq!(game.to_string(), "Game 7:");
+        assert_eq!(Game::parse_from_description(&game.to_string()), game);
+    }
+
+    #[test]
+    fn test_validate() {
+        let bag = ColourSet::new(14, 13, 12);
+
+        let game =
+            Game::parse_from_description("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+        assert_eq!(game.validate(&bag), vec![]);
+
+        let game = Game::parse_from_description(
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+        );
+        let violations = game.validate(&bag);
+        assert_eq!(
+            violations,
+            vec![
+                Violation {
+                    subset_index: 2,
+                    colour: Colour::Blue,
+                    drawn: 15,
+                    excess: 1,
+                },
+                Violation {
+                    subset_index: 2,
+                    colour: Colour::Red,
+                    drawn: 14,
+                    excess: 2,
+                },
+            ]
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "subset 3: drew 14 red, 2 more than the bag holds"
+        );
+    }
+
+    #[test]
+    fn test_is_set_valid_matches_minimal_set() {
+        let bags = [
+            ColourSet::new(14, 13, 12),
+            ColourSet::new(5, 5, 5),
+            ColourSet::new(0, 20, 3),
+        ];
+
+        for (seed, generated_bag) in bags.iter().enumerate() {
+            let log = generator::generate(&GeneratorConfig {
+                bag: *generated_bag,
+                games: 200,
+                draws: 6,
+                seed: seed as u64,
+            });
+
+            for game in log.contents.lines().map(Game::parse_from_description) {
+                let minimal_set = game.generate_minimal_set();
+
+                for bag in &bags {
+                    let fits_minimal_set = minimal_set.num_blue <= bag.num_blue
+                        && minimal_set.num_green <= bag.num_green
+                        && minimal_set.num_red <= bag.num_red;
+
+                    assert_eq!(game.is_set_valid(bag), fits_minimal_set, "{}", game);
+                    assert_eq!(game.validate(bag).is_empty(), fits_minimal_set, "{}", game);
+                }
+
+                // The minimal set is always valid, and removing any cube from it is not
+                assert!(game.is_set_valid(&minimal_set));
+                for colour in Colour::ALL {
+                    if minimal_set.count(colour) > 0 {
+                        let smaller = minimal_set - ColourSet::single(colour, 1);
+                        assert!(!game.is_set_valid(&smaller), "{}", game);
+                    }
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,253 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Colour, ColourSet, Game};
+
+/// How a [`GameParser`] handles a colour that appears more than once in a subset,
+/// such as `3 blue, 2 blue`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicatePolicy {
+    /// Add the counts together, giving `5 blue`.
+    Sum,
+    /// Keep the largest count, giving `3 blue`.
+    Max,
+    /// Keep the last count, giving `2 blue`.
+    #[default]
+    LastWins,
+    /// Reject the subset.
+    Error,
+}
+
+impl DuplicatePolicy {
+    /// Combines the count already seen for a colour with another count for it.
+    pub fn combine(&self, colour: Colour, existing: u32, num: u32) -> Result<u32, ParseError> {
+        match self {
+            DuplicatePolicy::Sum => existing
+                .checked_add(num)
+                .ok_or(ParseError::CountOverflow(colour)),
+            DuplicatePolicy::Max => Ok(existing.max(num)),
+            DuplicatePolicy::LastWins => Ok(num),
+            DuplicatePolicy::Error => Err(ParseError::DuplicateColour(colour)),
+        }
+    }
+}
+
+impl FromStr for DuplicatePolicy {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sum" => Ok(DuplicatePolicy::Sum),
+            "max" => Ok(DuplicatePolicy::Max),
+            "last" => Ok(DuplicatePolicy::LastWins),
+            "error" => Ok(DuplicatePolicy::Error),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseError {
+    MissingColon,
+    InvalidGameId(String),
+    /// A draw that isn't a count followed by a colour.
+    InvalidDraw(String),
+    InvalidCount(String),
+    UnknownColour(String),
+    DuplicateColour(Colour),
+    CountOverflow(Colour),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::MissingColon => write!(f, "expected ':' after the game ID"),
+            ParseError::InvalidGameId(id) => write!(f, "invalid game ID '{}'", id),
+            ParseError::InvalidDraw(draw) => {
+                write!(f, "expected a count and colour but found '{}'", draw)
+            }
+            ParseError::InvalidCount(count) => write!(f, "invalid count '{}'", count),
+            ParseError::UnknownColour(colour) => write!(f, "unknown colour '{}'", colour),
+            ParseError::DuplicateColour(colour) => {
+                write!(f, "{} appears more than once in a subset", colour)
+            }
+            ParseError::CountOverflow(colour) => write!(f, "too many {} cubes", colour),
+        }
+    }
+}
+
+/// Collects the counts drawn in a single subset, applying a [`DuplicatePolicy`].
+pub struct SubsetBuilder {
+    duplicates: DuplicatePolicy,
+    counts: [Option<u32>; 3],
+}
+
+impl SubsetBuilder {
+    pub fn new(duplicates: DuplicatePolicy) -> Self {
+        Self {
+            duplicates,
+            counts: [None; 3],
+        }
+    }
+
+    pub fn add(&mut self, colour: Colour, num: u32) -> Result<(), ParseError> {
+        let count = &mut self.counts[colour.index()];
+        *count = Some(match count {
+            Some(existing) => self.duplicates.combine(colour, *existing, num)?,
+            None => num,
+        });
+        Ok(())
+    }
+
+    pub fn build(&self) -> ColourSet {
+        Colour::ALL
+            .into_iter()
+            .map(|colour| ColourSet::single(colour, self.counts[colour.index()].unwrap_or(0)))
+            .sum()
+    }
+}
+
+/// Parses games from the puzzle's text format, allowing any amount of whitespace
+/// around the separators, e.g. `Game 1:3 blue,4 red ;  2 green`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GameParser {
+    pub duplicates: DuplicatePolicy,
+}
+
+impl GameParser {
+    pub fn new(duplicates: DuplicatePolicy) -> Self {
+        Self { duplicates }
+    }
+
+    /// Parses a [`Game`] from a description string, which has no subsets if nothing follows the colon.
+    /// Example: `Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green`
+    pub fn parse_game(&self, game_description: &str) -> Result<Game, ParseError> {
+        let (id, subsets) = game_description
+            .split_once(':')
+            .ok_or(ParseError::MissingColon)?;
+
+        let id = id.trim();
+        let game_id = id
+            .strip_prefix("Game")
+            .and_then(|id| id.trim_start().parse::<u32>().ok())
+            .ok_or_else(|| ParseError::InvalidGameId(id.to_string()))?;
+
+        let mut game = Game::new(game_id);
+        if subsets.trim().is_empty() {
+            return Ok(game);
+        }
+        for subset in subsets.split(';') {
+            game.add_subset(self.parse_subset(subset)?);
+        }
+
+        Ok(game)
+    }
+
+    /// Parses a single subset of a game.
+    /// Example: `1 red, 2 green, 6 blue`
+    pub fn parse_subset(&self, set_description: &str) -> Result<ColourSet, ParseError> {
+        let mut builder = SubsetBuilder::new(self.duplicates);
+
+        for draw in set_description.split(',') {
+            let mut parts = draw.split_whitespace();
+            let (Some(num), Some(colour), None) = (parts.next(), parts.next(), parts.next()) else {
+                return Err(ParseError::InvalidDraw(draw.trim().to_string()));
+            };
+
+            let num = num
+                .parse::<u32>()
+                .map_err(|_| ParseError::InvalidCount(num.to_string()))?;
+            let colour = colour
+                .parse::<Colour>()
+                .map_err(|_| ParseError::UnknownColour(colour.to_string()))?;
+
+            builder.add(colour, num)?;
+        }
+
+        Ok(builder.build())
+    }
+}
+
+#[cfg(test)]
+mod parser_tests {
+    use super::*;
+
+    #[test]
+    fn test_flexible_whitespace() {
+        let parser = GameParser::default();
+        let expected =
+            Game::parse_from_description("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green");
+
+        for description in [
+            "Game 1:3 blue,4 red;1 red,2 green,6 blue;2 green",
+            "  Game  1 :  3 blue ,  4 red ;\t1 red,  2 green, 6  blue;2 green  ",
+            "Game1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+        ] {
+            assert_eq!(parser.parse_game(description), Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn test_duplicate_policies() {
+        let subset = "3 blue, 4 red, 2 blue";
+
+        let parse = |duplicates| GameParser::new(duplicates).parse_subset(subset);
+
+        assert_eq!(parse(DuplicatePolicy::Sum), Ok(ColourSet::new(5, 0, 4)));
+        assert_eq!(parse(DuplicatePolicy::Max), Ok(ColourSet::new(3, 0, 4)));
+        assert_eq!(
+            parse(DuplicatePolicy::LastWins),
+            Ok(ColourSet::new(2, 0, 4))
+        );
+        assert_eq!(
+            parse(DuplicatePolicy::Error),
+            Err(ParseError::DuplicateColour(Colour::Blue))
+        );
+
+        // A duplicate is an error even when the first count is zero
+        assert_eq!(
+            GameParser::new(DuplicatePolicy::Error).parse_subset("0 red, 1 red"),
+            Err(ParseError::DuplicateColour(Colour::Red))
+        );
+    }
+
+    #[test]
+    fn test_sum_overflow() {
+        let parser = GameParser::new(DuplicatePolicy::Sum);
+
+        assert_eq!(
+            parser.parse_subset(&format!("{} green, 1 green", u32::MAX)),
+            Err(ParseError::CountOverflow(Colour::Green))
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let parser = GameParser::default();
+
+        assert_eq!(
+            parser.parse_game("Game 1 3 blue"),
+            Err(ParseError::MissingColon)
+        );
+        assert_eq!(
+            parser.parse_game("Round 1: 3 blue"),
+            Err(ParseError::InvalidGameId("Round 1".to_string()))
+        );
+        assert_eq!(
+            parser.parse_game("Game 1: 3 blue; "),
+            Err(ParseError::InvalidDraw("".to_string()))
+        );
+        assert_eq!(
+            parser.parse_game("Game 1: 3 blue 4 red"),
+            Err(ParseError::InvalidDraw("3 blue 4 red".to_string()))
+        );
+        assert_eq!(
+            parser.parse_game("Game 1: three blue"),
+            Err(ParseError::InvalidCount("three".to_string()))
+        );
+        assert_eq!(
+            parser.parse_game("Game 1: 3 purple"),
+            Err(ParseError::UnknownColour("purple".to_string()))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use rng::Rng;
+
+use crate::game::{Colour, ColourSet};
+
+/// Settings for [`generate`].
+pub struct GeneratorConfig {
+    /// The bag used to decide which games are possible for part one.
+    pub bag: ColourSet,
+    pub games: usize,
+    /// The maximum number of subsets drawn in each game.
+    pub draws: usize,
+    pub seed: u64,
+}
+
+/// A generated game log, alongside the answers expected for it.
+pub struct GeneratedLog {
+    pub contents: String,
+    pub valid_game_ids_sum: u64,
+    /// Calculated exactly, as large bags give powers that overflow 64 bits.
+    pub minimal_sets_power_sum: u128,
+}
+
+/// Generates a game log in the puzzle's format.
+///
+/// Each draw contains a random selection of colours in a random order.
+/// Counts may exceed the bag by up to a quarter, so that a realistic mix of games is possible.
+/// The expected answers are tracked while generating, independently of [`crate::game::Game`].
+pub fn generate(config: &GeneratorConfig) -> GeneratedLog {
+    let mut rng = Rng::new(config.seed);
+
+    let mut lines = Vec::with_capacity(config.games);
+    let mut valid_game_ids_sum = 0;
+    let mut minimal_sets_power_sum = 0;
+
+    for id in 1..=config.games as u32 {
+        let num_draws = rng.range(1, config.draws.max(1) as u64);
+        let mut maximums = [0; 3];
+        let mut draws = Vec::new();
+
+        for _ in 0..num_draws {
+            let mut colours = Colour::ALL;
+            // Fisher-Yates shuffle, so colours appear in any order
+            for i in (1..colours.len()).rev() {
+                colours.swap(i, rng.range(0, i as u64) as usize);
+            }
+            let num_colours = rng.range(1, colours.len() as u64) as usize;
+
+            let mut parts = Vec::new();
+            for colour in &colours[..num_colours] {
+                let in_bag = config.bag.count(*colour);
+                let num = rng.range(1, in_bag.saturating_add(in_bag / 4).max(1) as u64) as u32;
+
+                maximums[colour.index()] = maximums[colour.index()].max(num);
+                parts.push(format!("{} {}", num, colour));
+            }
+
+            draws.push(parts.join(", "));
+        }
+
+        if Colour::ALL
+            .iter()
+            .all(|colour| maximums[colour.index()] <= config.bag.count(*colour))
+        {
+            valid_game_ids_sum += id as u64;
+        }
+        minimal_sets_power_sum += maximums.iter().map(|num| *num as u128).product::<u128>();
+
+        lines.push(format!("Game {}: {}", id, draws.join("; ")));
+    }
+
+    GeneratedLog {
+        contents: lines.join("\n"),
+        valid_game_ids_sum,
+        minimal_sets_power_sum,
+    }
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use super::*;
+
+    fn config(seed: u64) -> GeneratorConfig {
+        GeneratorConfig {
+            bag: ColourSet::new(14, 13, 12),
+            games: 100,
+            draws: 6,
+            seed,
+        }
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let first = generate(&config(7));
+        let second = generate(&config(7));
+        let other = generate(&config(8));
+
+        assert_eq!(first.contents, second.contents);
+        assert_eq!(first.valid_game_ids_sum, second.valid_game_ids_sum);
+        assert_ne!(first.contents, other.contents);
+    }
+
+    #[test]
+    fn test_generate_shape() {
+        let log = generate(&config(1));
+        let lines = log.contents.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 100);
+        for (index, line) in lines.iter().enumerate() {
+            assert!(line.starts_with(&format!("Game {}: ", index + 1)));
+            assert!(line.split("; ").count() <= 6);
+        }
+
+        // Some, but not all, games should be possible with the bag
+        assert!(log.valid_game_ids_sum > 0);
+        assert!(log.valid_game_ids_sum < (1..=100).sum::<u64>());
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+use crate::game::{self, Colour, Game, GameParser, SubsetBuilder};
+
+/// The formats a game log can be loaded from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputFormat {
+    /// The puzzle's own format, e.g. `Game 1: 3 blue, 4 red; 2 green`.
+    Text,
+    /// An array of games, each with a list of subsets mapping colours to counts.
+    /// Example: `[{"id": 1, "subsets": [{"blue": 3, "red": 4}, {"green": 2}]}]`
+    Json,
+    /// A header row naming a `game` column and any colour columns, followed by one row per subset.
+    /// Example: `game,blue,green,red` then `1,3,,4` and `1,0,2,0`
+    ///
+    /// A colour may have several columns, which are combined using the parser's [`game::DuplicatePolicy`].
+    Csv,
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Text {
+        line: usize,
+        error: game::ParseError,
+    },
+    Json(serde_json::Error),
+    Csv {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Text { line, error } => {
+                write!(f, "invalid game on line {}: {}", line, error)
+            }
+            ParseError::Json(err) => write!(f, "invalid JSON: {}", err),
+            ParseError::Csv { line, message } => {
+                write!(f, "invalid CSV on line {}: {}", line, message)
+            }
+        }
+    }
+}
+
+impl InputFormat {
+    /// Detects the format of a game log from its first line.
+    pub fn detect(contents: &str) -> InputFormat {
+        let contents = contents.trim_start();
+        let first_line = contents.lines().next().unwrap_or_default();
+
+        if contents.starts_with('[') {
+            InputFormat::Json
+        } else if first_line
+            .split(',')
+            .any(|column| column.trim().eq_ignore_ascii_case("game"))
+        {
+            InputFormat::Csv
+        } else {
+            InputFormat::Text
+        }
+    }
+}
+
+/// Parses games from a log in any [`InputFormat`], detecting which automatically.
+pub fn parse_games(contents: &str, parser: &GameParser) -> Result<Vec<Game>, ParseError> {
+    match InputFormat::detect(contents) {
+        InputFormat::Text => contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                parser.parse_game(line).map_err(|error| ParseError::Text {
+                    line: index + 1,
+                    error,
+                })
+            })
+            .collect(),
+        InputFormat::Json => parse_json(contents, parser),
+        InputFormat::Csv => parse_csv(contents, parser),
+    }
+}
+
+#[derive(Deserialize)]
+struct JsonGame {
+    id: u32,
+    subsets: Vec<JsonSubset>,
+}
+
+/// The colours and counts of a subset, in the order they appear, keeping any repeated colours.
+struct JsonSubset(Vec<(String, u32)>);
+
+impl<'de> Deserialize<'de> for JsonSubset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SubsetVisitor;
+
+        impl<'de> Visitor<'de> for SubsetVisitor {
+            type Value = JsonSubset;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an object mapping colours to counts")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut draws = Vec::new();
+                while let Some(draw) = map.next_entry()? {
+                    draws.push(draw);
+                }
+                Ok(JsonSubset(draws))
+            }
+        }
+
+        deserializer.deserialize_map(SubsetVisitor)
+    }
+}
+
+fn parse_json(contents: &str, parser: &GameParser) -> Result<Vec<Game>, ParseError> {
+    let json_games: Vec<JsonGame> = serde_json::from_str(contents).map_err(ParseError::Json)?;
+    let error = |id: u32, message: String| {
+        ParseError::Json(serde::de::Error::custom(format!(
+            "{} in game {}",
+            message, id
+        )))
+    };
+
+    let mut games = Vec::with_capacity(json_games.len());
+    for json_game in json_games {
+        let mut game = Game::new(json_game.id);
+
+        for JsonSubset(draws) in json_game.subsets {
+            let mut builder = SubsetBuilder::new(parser.duplicates);
+            for (colour, num) in draws {
+                let colour = parse_colour(&colour)
+                    .ok_or_else(|| error(json_game.id, format!("unknown colour '{}'", colour)))?;
+                builder
+                    .add(colour, num)
+                    .map_err(|err| error(json_game.id, err.to_string()))?;
+            }
+            game.add_subset(builder.build());
+        }
+
+        games.push(game);
+    }
+
+    Ok(games)
+}
+
+/// Parses a colour named by a JSON key or CSV header, ignoring case and surrounding whitespace.
+fn parse_colour(name: &str) -> Option<Colour> {
+    name.trim().to_ascii_lowercase().parse().ok()
+}
+
+enum CsvColumn {
+    Game,
+    Colour(Colour),
+}
+
+fn parse_csv(contents: &str, parser: &GameParser) -> Result<Vec<Game>, ParseError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    let (header_line, header) = lines.next().unwrap_or_default();
+    let error = |line, message: String| ParseError::Csv { line, message };
+
+    let columns = header
+        .split(',')
+        .map(|column| match column.trim().to_ascii_lowercase().as_str() {
+            "game" => Ok(CsvColumn::Game),
+            name => parse_colour(name)
+                .map(CsvColumn::Colour)
+                .ok_or_else(|| error(header_line, format!("unknown column '{}'", name))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut games: Vec<Game> = Vec::new();
+    // The index in `games` of each game ID, as a game's rows need not be consecutive
+    let mut game_indexes = HashMap::new();
+    for (line, row) in lines {
+        let cells = row.split(',').map(str::trim).collect::<Vec<_>>();
+        if cells.len() != columns.len() {
+            return Err(error(
+                line,
+                format!("expected {} cells but found {}", columns.len(), cells.len()),
+            ));
+        }
+
+        let mut id = None;
+        let mut builder = SubsetBuilder::new(parser.duplicates);
+        for (column, cell) in columns.iter().zip(cells) {
+            let num = match cell {
+                "" => None,
+                cell => Some(
+                    cell.parse::<u32>()
+                        .map_err(|_| error(line, format!("'{}' is not a count", cell)))?,
+                ),
+            };
+
+            match (column, num) {
+                (CsvColumn::Game, _) => id = num,
+                (CsvColumn::Colour(colour), Some(num)) => builder
+                    .add(*colour, num)
+                    .map_err(|err| error(line, err.to_string()))?,
+                // A blank cell means the colour wasn't drawn, so it can't count as a duplicate
+                (CsvColumn::Colour(_), None) => {}
+            }
+        }
+
+        let id = id.ok_or_else(|| error(line, "missing game ID".to_string()))?;
+        let set = builder.build();
+        let index = *game_indexes.entry(id).or_insert_with(|| {
+            games.push(Game::new(id));
+            games.len() - 1
+        });
+        games[index].add_subset(set);
+    }
+
+    Ok(games)
+}
+
+#[cfg(test)]
+mod input_tests {
+    use super::*;
+    use crate::game::{ColourSet, DuplicatePolicy};
+
+    fn parse(contents: &str) -> Result<Vec<Game>, ParseError> {
+        parse_games(contents, &GameParser::default())
+    }
+
+    const TEXT_CONTENTS: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue"#;
+
+    const JSON_CONTENTS: &str = r#"[
+    {"id": 1, "subsets": [{"blue": 3, "red": 4}, {"red": 1, "green": 2, "blue": 6}, {"green": 2}]},
+    {"id": 2, "subsets": [{"blue": 1, "green": 2}, {"green": 3, "blue": 4, "red": 1}, {"green": 1, "blue": 1}]}
+]"#;
+
+    const CSV_CONTENTS: &str = r#"game,red,green,blue
+1,4,,3
+1,1,2,6
+1,0,2,0
+2,,2,1
+2,1,3,4
+2,,1,1"#;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(InputFormat::detect(TEXT_CONTENTS), InputFormat::Text);
+        assert_eq!(InputFormat::detect(JSON_CONTENTS), InputFormat::Json);
+        assert_eq!(InputFormat::detect(CSV_CONTENTS), InputFormat::Csv);
+        assert_eq!(InputFormat::detect(" Game , Blue\n1, 2"), InputFormat::Csv);
+    }
+
+    #[test]
+    fn test_formats_agree() {
+        let text = parse(TEXT_CONTENTS).unwrap();
+
+        assert_eq!(parse(JSON_CONTENTS).unwrap(), text);
+        assert_eq!(parse(CSV_CONTENTS).unwrap(), text);
+    }
+
+    #[test]
+    fn test_colour_names_ignore_case() {
+        let json = parse(r#"[{"id": 1, "subsets": [{"Blue": 3, " RED ": 4}]}]"#).unwrap();
+        let csv = parse("Game, Blue ,RED\n1,3,4").unwrap();
+
+        assert_eq!(json[0].subsets, vec![ColourSet::new(3, 0, 4)]);
+        assert_eq!(csv, json);
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let err = parse(r#"[{"id": 1, "subsets": [{"purple": 3}]}]"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("unknown colour 'purple' in game 1"));
+
+        assert!(matches!(parse(r#"[{"id": 1}]"#), Err(ParseError::Json(_))));
+    }
+
+    #[test]
+    fn test_csv_errors() {
+        let err = parse("game,purple\n1,2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CSV on line 1: unknown column 'purple'"
+        );
+
+        let err = parse("game,blue\n1,2\n2").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CSV on line 3: expected 2 cells but found 1"
+        );
+
+        let err = parse("game,blue\n1,many").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CSV on line 2: 'many' is not a count"
+        );
+
+        let err = parse("blue,game\n2,").unwrap_err();
+        assert_eq!(err.to_string(), "invalid CSV on line 2: missing game ID");
+    }
+
+    #[test]
+    fn test_text_errors() {
+        let err = parse("Game 1: 3 blue\n\nGame 2: 3 purple").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid game on line 3: unknown colour 'purple'"
+        );
+    }
+
+    #[test]
+    fn test_json_duplicate_colours() {
+        let contents = r#"[{"id": 1, "subsets": [{"blue": 3, "red": 4, "blue": 2}]}]"#;
+
+        let parse = |duplicates| {
+            parse_games(contents, &GameParser::new(duplicates)).map(|games| games[0].subsets[0])
+        };
+        assert_eq!(
+            parse(DuplicatePolicy::Sum).unwrap(),
+            ColourSet::new(5, 0, 4)
+        );
+        assert_eq!(
+            parse(DuplicatePolicy::Max).unwrap(),
+            ColourSet::new(3, 0, 4)
+        );
+        assert_eq!(
+            parse(DuplicatePolicy::LastWins).unwrap(),
+            ColourSet::new(2, 0, 4)
+        );
+
+        let err = parse(DuplicatePolicy::Error).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid JSON: blue appears more than once in a subset in game 1"
+        );
+    }
+
+    #[test]
+    fn test_csv_interleaved_games() {
+        let games = parse("game,blue\n2,1\n1,2\n2,3").unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!((games[0].id, games[0].subsets.len()), (2, 2));
+        assert_eq!(games[0].subsets[1], ColourSet::new(3, 0, 0));
+        assert_eq!((games[1].id, games[1].subsets.len()), (1, 1));
+    }
+
+    #[test]
+    fn test_csv_duplicate_columns() {
+        let contents = "game,blue,red,blue\n1,3,4,2";
+
+        let games = parse_games(contents, &GameParser::new(DuplicatePolicy::Sum)).unwrap();
+        assert_eq!(games[0].subsets, vec![ColourSet::new(5, 0, 4)]);
+
+        let err = parse_games(contents, &GameParser::new(DuplicatePolicy::Error)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CSV on line 2: blue appears more than once in a subset"
+        );
+    }
+
+    #[test]
+    fn test_csv_duplicate_columns_with_blank_cell() {
+        let contents = "game,blue,red,blue\n1,,4,2\n2,3,,";
+
+        let games = parse_games(contents, &GameParser::new(DuplicatePolicy::Error)).unwrap();
+        assert_eq!(games[0].subsets, vec![ColourSet::new(2, 0, 4)]);
+        assert_eq!(games[1].subsets, vec![ColourSet::new(3, 0, 0)]);
+    }
+}
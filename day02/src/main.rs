@@ -1,55 +1,315 @@
 mod game;
+mod generator;
+mod input;
+mod optimiser;
+mod query;
+mod report;
+#[cfg(test)]
+mod test_fixtures;
 
-use game::Game;
+use game::{ColourSet, DuplicatePolicy, Game, GameParser, OverflowError};
+use generator::GeneratorConfig;
+use optimiser::{CubeCost, Objective};
+use query::Query;
+use report::Report;
 
 fn main() {
-    let puzzle_input = include_str!("../input.txt");
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let parser = take_parser_options(&mut args);
+
+    match args.first().map(String::as_str) {
+        Some("format") => format_file(args.get(1), &parser),
+        Some("query") => run_query(args.get(1), args.get(2), &parser),
+        Some("generate") => generate_log(&args[1..]),
+        Some("--explain") => explain(args.get(1), &parser),
+        Some("optimise") => optimise(&args[1..], &parser),
+        Some("report") => print_report(&args[1..], &parser),
+        _ => solve(include_str!("../input.txt"), &parser),
+    }
+}
+
+/// Removes `--duplicates <sum|max|last|error>` from the arguments, returning the [`GameParser`] it configures.
+fn take_parser_options(args: &mut Vec<String>) -> GameParser {
+    let Some(index) = args.iter().position(|arg| arg == "--duplicates") else {
+        return GameParser::default();
+    };
 
-    let games = parse_games(puzzle_input);
+    let duplicates = args
+        .get(index + 1)
+        .and_then(|policy| policy.parse::<DuplicatePolicy>().ok())
+        .unwrap_or_else(|| {
+            eprintln!("Usage: --duplicates <sum|max|last|error>");
+            std::process::exit(1);
+        });
+    args.drain(index..index + 2);
+
+    GameParser::new(duplicates)
+}
+
+fn solve(puzzle_input: &str, parser: &GameParser) {
+    let games = parse_games(puzzle_input, parser);
 
     // Part 1
     let set = game::ColourSet::new(14, 13, 12);
-    let valid_game_ids_sum = games
-        .iter()
-        .filter(|game| game.is_set_valid(&set))
-        .map(|game| game.id)
-        .sum::<u32>();
+    let valid_game_ids_sum = exit_on_overflow(valid_game_ids_sum(&games, &set));
 
     println!("Sum of valid game IDs: {}", valid_game_ids_sum);
 
     // Part 2
-    let minimal_sets_power_sum = games
-        .iter()
-        .map(|game| game.generate_minimal_set().power())
-        .sum::<u32>();
+    let minimal_sets_power_sum = exit_on_overflow(minimal_sets_power_sum(&games));
 
     println!("Sum of minimal sets' power: {}", minimal_sets_power_sum);
 }
 
-fn parse_games(contents: &str) -> Vec<Game> {
-    let mut games = Vec::new();
+/// Returns the sum of the IDs of the games that are possible with the given set.
+fn valid_game_ids_sum(games: &[Game], set: &ColourSet) -> Result<u64, OverflowError> {
+    games
+        .iter()
+        .filter(|game| game.is_set_valid(set))
+        .try_fold(0u64, |sum, game| {
+            sum.checked_add(game.id as u64).ok_or(OverflowError {
+                operation: "sum of valid game IDs",
+            })
+        })
+}
+
+/// Returns the sum of the power of each game's minimal set.
+fn minimal_sets_power_sum(games: &[Game]) -> Result<u64, OverflowError> {
+    games.iter().try_fold(0u64, |sum, game| {
+        let power = game.generate_minimal_set().power()?;
+        sum.checked_add(power).ok_or(OverflowError {
+            operation: "sum of minimal sets' power",
+        })
+    })
+}
+
+fn exit_on_overflow<T>(result: Result<T, OverflowError>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
 
-    for line in contents.lines() {
-        let game = Game::parse_from_description(line);
-        games.push(game);
+/// Prints why each game is impossible with the part one bag,
+/// reading games from the given file, or the puzzle input if no file is given.
+fn explain(path: Option<&String>, parser: &GameParser) {
+    let contents = read_file_or_input(path);
+
+    let set = game::ColourSet::new(14, 13, 12);
+    for game in parse_games(&contents, parser) {
+        let violations = game.validate(&set);
+        if violations.is_empty() {
+            continue;
+        }
+
+        println!("Game {} is impossible:", game.id);
+        for violation in violations {
+            println!("  {}", violation);
+        }
     }
+}
 
-    games
+/// Prints the best bag within a budget, and the Pareto frontier of bag choices.
+/// Arguments: `<budget> [--ids] [--cost <blue> <green> <red>] [path]`
+fn optimise(args: &[String], parser: &GameParser) {
+    let usage = || -> ! {
+        eprintln!("Usage: day02 optimise <budget> [--ids] [--cost <blue> <green> <red>] [path]");
+        std::process::exit(1);
+    };
+
+    let Some(budget) = args.first().and_then(|budget| budget.parse::<u64>().ok()) else {
+        usage();
+    };
+
+    let mut objective = Objective::GameCount;
+    let mut cost = CubeCost::PER_CUBE;
+    let mut path = None;
+
+    let mut args = args[1..].iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ids" => objective = Objective::IdSum,
+            "--cost" => {
+                let mut next = || {
+                    args.next()
+                        .and_then(|arg| arg.parse().ok())
+                        .unwrap_or_else(|| usage())
+                };
+                cost = CubeCost {
+                    blue: next(),
+                    green: next(),
+                    red: next(),
+                };
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let games = parse_games(&read_file_or_input(path), parser);
+    let frontier = optimiser::pareto_frontier(&games, &cost, objective);
+
+    match optimiser::best_bag(&frontier, budget) {
+        Some(best) => println!(
+            "Best bag within {}: {} (cost {}, score {})",
+            budget, best.bag, best.cost, best.score
+        ),
+        None => println!("No bag fits within {}", budget),
+    }
+
+    println!("Pareto frontier:");
+    for choice in frontier {
+        println!(
+            "  cost {:>6}  score {:>6}  {}",
+            choice.cost, choice.score, choice.bag
+        );
+    }
+}
+
+/// Prints statistics about the games, relative to a bag.
+/// Arguments: `[--csv] [--bag <blue> <green> <red>] [path]`
+fn print_report(args: &[String], parser: &GameParser) {
+    let usage = || -> ! {
+        eprintln!("Usage: day02 report [--csv] [--bag <blue> <green> <red>] [path]");
+        std::process::exit(1);
+    };
+
+    let mut is_csv = false;
+    let mut bag = ColourSet::new(14, 13, 12);
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--csv" => is_csv = true,
+            "--bag" => {
+                let mut next = || {
+                    args.next()
+                        .and_then(|arg| arg.parse().ok())
+                        .unwrap_or_else(|| usage())
+                };
+                bag = ColourSet::new(next(), next(), next());
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    let games = parse_games(&read_file_or_input(path), parser);
+    let report = exit_on_overflow(Report::new(&games, &bag));
+
+    if is_csv {
+        let mut csv = String::new();
+        report.write_csv(&mut csv).unwrap();
+        print!("{}", csv);
+    } else {
+        print!("{}", report);
+    }
+}
+
+/// Prints every game in the given file, in any input format, in its canonical text form.
+fn format_file(path: Option<&String>, parser: &GameParser) {
+    let Some(path) = path else {
+        eprintln!("Usage: day02 format <path>");
+        std::process::exit(1);
+    };
+
+    let contents = read_file(path);
+    for game in parse_games(&contents, parser) {
+        println!("{}", game);
+    }
+}
+
+/// Evaluates a [`Query`] against the games in the given file, or the puzzle input if no file is given.
+fn run_query(query: Option<&String>, path: Option<&String>, parser: &GameParser) {
+    let Some(query) = query else {
+        eprintln!("Usage: day02 query <query> [path]");
+        std::process::exit(1);
+    };
+
+    let query = Query::parse(query).unwrap_or_else(|err| {
+        eprintln!("Invalid query {}", err);
+        std::process::exit(1);
+    });
+
+    let contents = read_file_or_input(path);
+
+    let result = exit_on_overflow(query.evaluate(&parse_games(&contents, parser)));
+    println!("{}", result);
+}
+
+/// Prints a generated game log, and writes its expected answers to stderr.
+/// Arguments: `<seed> [games] [draws] [blue green red]`
+fn generate_log(args: &[String]) {
+    let numbers = args
+        .iter()
+        .map(|arg| arg.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>();
+
+    let config = match numbers.as_deref() {
+        Ok([seed, rest @ ..]) if matches!(rest.len(), 0 | 1 | 2 | 5) => {
+            let bag = match rest {
+                [_, _, blue, green, red] => {
+                    let count = |num: &u64| u32::try_from(*num).ok();
+                    count(blue)
+                        .zip(count(green))
+                        .zip(count(red))
+                        .map(|((blue, green), red)| ColourSet::new(blue, green, red))
+                }
+                _ => Some(ColourSet::new(14, 13, 12)),
+            };
+
+            bag.map(|bag| GeneratorConfig {
+                seed: *seed,
+                games: rest.first().map_or(100, |games| *games as usize),
+                draws: rest.get(1).map_or(6, |draws| *draws as usize),
+                bag,
+            })
+        }
+        _ => None,
+    };
+
+    let Some(config) = config else {
+        eprintln!("Usage: day02 generate <seed> [games] [draws] [blue green red]");
+        std::process::exit(1);
+    };
+
+    let log = generator::generate(&config);
+    println!("{}", log.contents);
+    eprintln!("Sum of valid game IDs: {}", log.valid_game_ids_sum);
+    eprintln!("Sum of minimal sets' power: {}", log.minimal_sets_power_sum);
+}
+
+fn read_file_or_input(path: Option<&String>) -> String {
+    match path {
+        Some(path) => read_file(path),
+        None => include_str!("../input.txt").to_string(),
+    }
+}
+
+fn read_file(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {}", path, err);
+        std::process::exit(1);
+    })
+}
+
+/// Parses games from a log in any of the supported input formats, exiting if it is invalid.
+fn parse_games(contents: &str, parser: &GameParser) -> Vec<Game> {
+    input::parse_games(contents, parser).unwrap_or_else(|err| {
+        eprintln!("Failed to parse games: {}", err);
+        std::process::exit(1);
+    })
 }
 
 #[cfg(test)]
 mod day02_tests {
     use super::*;
-
-    const GAME_CONTENTS: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+    use crate::test_fixtures::GAME_CONTENTS;
 
     #[test]
     fn test_parse_games() {
-        let games = parse_games(GAME_CONTENTS);
+        let games = parse_games(GAME_CONTENTS, &GameParser::default());
 
         assert_eq!(games.len(), 5);
 
@@ -111,9 +371,110 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
         assert_eq!(games[4].subsets[1].num_red, 1);
     }
 
+    #[test]
+    fn test_format_round_trip() {
+        let games = parse_games(GAME_CONTENTS, &GameParser::default());
+
+        let formatted = games
+            .iter()
+            .map(|game| game.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(parse_games(&formatted, &GameParser::default()), games);
+    }
+
+    #[test]
+    fn test_format_round_trip_without_subsets() {
+        let games = parse_games(r#"[{"id": 1, "subsets": []}]"#, &GameParser::default());
+        let formatted = games[0].to_string();
+
+        assert_eq!(formatted, "Game 1:");
+        assert_eq!(parse_games(&formatted, &GameParser::default()), games);
+    }
+
+    #[test]
+    fn test_parse_generated_games() {
+        for seed in 0..20 {
+            let bag = game::ColourSet::new(14, 13, 12);
+            let log = generator::generate(&GeneratorConfig {
+                bag,
+                games: 1000,
+                draws: 10,
+                seed,
+            });
+
+            let games = parse_games(&log.contents, &GameParser::default());
+            assert_eq!(games.len(), 1000);
+
+            assert_eq!(valid_game_ids_sum(&games, &bag), Ok(log.valid_game_ids_sum));
+            assert_eq!(
+                minimal_sets_power_sum(&games).map(u128::from),
+                Ok(log.minimal_sets_power_sum)
+            );
+        }
+    }
+
+    #[test]
+    fn test_sums_beyond_u32() {
+        let bag = game::ColourSet::new(5_000, 4_000, 3_000);
+        let log = generator::generate(&GeneratorConfig {
+            bag,
+            games: 1000,
+            draws: 6,
+            seed: 1,
+        });
+        assert!(log.minimal_sets_power_sum > u32::MAX as u128);
+
+        let games = parse_games(&log.contents, &GameParser::default());
+        assert_eq!(valid_game_ids_sum(&games, &bag), Ok(log.valid_game_ids_sum));
+        assert_eq!(
+            minimal_sets_power_sum(&games).map(u128::from),
+            Ok(log.minimal_sets_power_sum)
+        );
+    }
+
+    #[test]
+    fn test_sums_overflow() {
+        let games = parse_games(
+            "Game 4294967295: 4294967295 blue, 4294967295 green, 1 red\nGame 4294967294: 1 blue",
+            &GameParser::default(),
+        );
+
+        // Neither sum fits in 32 bits, but both fit in 64
+        assert_eq!(
+            valid_game_ids_sum(&games, &ColourSet::new(u32::MAX, u32::MAX, u32::MAX)),
+            Ok(u32::MAX as u64 * 2 - 1)
+        );
+        assert_eq!(
+            minimal_sets_power_sum(&games),
+            Ok(u32::MAX as u64 * u32::MAX as u64)
+        );
+
+        let games = parse_games(
+            "Game 1: 4294967295 blue, 4294967295 green, 1 red\nGame 2: 4294967295 blue, 4294967295 green, 1 red",
+            &GameParser::default(),
+        );
+        assert_eq!(
+            minimal_sets_power_sum(&games),
+            Err(OverflowError {
+                operation: "sum of minimal sets' power"
+            })
+        );
+
+        let games = parse_games(
+            "Game 1: 4294967295 blue, 4294967295 green, 2 red",
+            &GameParser::default(),
+        );
+        assert_eq!(
+            minimal_sets_power_sum(&games),
+            Err(OverflowError { operation: "power" })
+        );
+    }
+
     #[test]
     fn test_valid_games() {
-        let games = parse_games(GAME_CONTENTS);
+        let games = parse_games(GAME_CONTENTS, &GameParser::default());
 
         let set = game::ColourSet::new(14, 13, 12);
 
@@ -127,7 +488,7 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
 
     #[test]
     fn test_minimal_set_generation() {
-        let games = parse_games(GAME_CONTENTS)
+        let games = parse_games(GAME_CONTENTS, &GameParser::default())
             .iter()
             .map(|game| game.generate_minimal_set())
             .collect::<Vec<_>>();
@@ -0,0 +1,330 @@
+use std::collections::BTreeSet;
+
+use crate::game::{Colour, ColourSet, Game};
+
+/// The price of a single cube of each colour.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CubeCost {
+    pub blue: u64,
+    pub green: u64,
+    pub red: u64,
+}
+
+impl CubeCost {
+    /// Every cube costs one, so a budget is a total number of cubes.
+    pub const PER_CUBE: CubeCost = CubeCost {
+        blue: 1,
+        green: 1,
+        red: 1,
+    };
+
+    /// Returns the cost of the bag, saturating at `u64::MAX` so that a bag
+    /// too expensive to price is never within budget.
+    pub fn of(&self, bag: &ColourSet) -> u64 {
+        Colour::ALL
+            .into_iter()
+            .map(|colour| {
+                self.per_cube(colour)
+                    .saturating_mul(bag.count(colour) as u64)
+            })
+            .fold(0, u64::saturating_add)
+    }
+
+    fn per_cube(&self, colour: Colour) -> u64 {
+        match colour {
+            Colour::Blue => self.blue,
+            Colour::Green => self.green,
+            Colour::Red => self.red,
+        }
+    }
+}
+
+/// What a bag is scored on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    /// The number of games the bag admits.
+    GameCount,
+    /// The sum of the IDs of the games the bag admits.
+    IdSum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BagChoice {
+    pub bag: ColourSet,
+    pub cost: u64,
+    pub score: u64,
+}
+
+/// Returns the Pareto frontier of bags, ordered by increasing cost.
+///
+/// Every bag on the frontier is the smallest bag admitting its games, and scores
+/// strictly more than every cheaper bag, so no other bag is both cheaper and better.
+///
+/// A game is admitted by any bag containing its minimal set, so only the counts appearing
+/// in some minimal set need to be considered as limits for blue and green. The minimal sets are
+/// sorted by red, so a single sweep over them finds the bags for every red limit at once.
+///
+/// # Cost
+/// With `n` games there are up to `n + 1` limits for each of blue and green, and each pair of
+/// limits takes a sweep over every game, so this takes `O(n³)` time in the worst case. Each sweep
+/// can add a candidate bag per game, and every candidate is kept and sorted before the frontier is
+/// picked out of them. This is fast for the puzzle's few hundred games, but grows quickly beyond that.
+pub fn pareto_frontier(games: &[Game], cost: &CubeCost, objective: Objective) -> Vec<BagChoice> {
+    let mut minimal_sets = games
+        .iter()
+        .map(|game| {
+            let score = match objective {
+                Objective::GameCount => 1,
+                Objective::IdSum => game.id as u64,
+            };
+            (game.generate_minimal_set(), score)
+        })
+        .collect::<Vec<_>>();
+    minimal_sets.sort_by_key(|(set, _)| set.num_red);
+
+    let limits = |colour| {
+        minimal_sets
+            .iter()
+            .map(|(set, _)| set.count(colour))
+            .chain([0])
+            .collect::<BTreeSet<_>>()
+    };
+
+    let mut choices = vec![BagChoice {
+        bag: ColourSet::default(),
+        cost: 0,
+        score: 0,
+    }];
+    for max_blue in limits(Colour::Blue) {
+        for max_green in limits(Colour::Green) {
+            let mut admitted = minimal_sets
+                .iter()
+                .filter(|(set, _)| set.num_blue <= max_blue && set.num_green <= max_green)
+                .peekable();
+
+            let mut bag = ColourSet::default();
+            let mut score = 0;
+            while let Some((set, game_score)) = admitted.next() {
+                bag = bag | *set;
+                score += game_score;
+
+                // Games with the same red count are admitted together
+                if admitted
+                    .peek()
+                    .is_some_and(|(next, _)| next.num_red == set.num_red)
+                {
+                    continue;
+                }
+                // Smaller limits already found this bag unless it reaches both of them
+                if bag.num_blue == max_blue && bag.num_green == max_green {
+                    choices.push(BagChoice {
+                        bag,
+                        cost: cost.of(&bag),
+                        score,
+                    });
+                }
+            }
+        }
+    }
+
+    choices.sort_by_key(|choice| {
+        (
+            choice.cost,
+            std::cmp::Reverse(choice.score),
+            choice.bag.num_blue,
+            choice.bag.num_green,
+            choice.bag.num_red,
+        )
+    });
+
+    let mut frontier: Vec<BagChoice> = Vec::new();
+    for choice in choices {
+        if frontier.last().is_none_or(|best| choice.score > best.score) {
+            frontier.push(choice);
+        }
+    }
+
+    frontier
+}
+
+/// Returns the best scoring bag costing no more than the budget, preferring the cheapest on ties.
+pub fn best_bag(frontier: &[BagChoice], budget: u64) -> Option<BagChoice> {
+    frontier
+        .iter()
+        .take_while(|choice| choice.cost <= budget)
+        .last()
+        .copied()
+}
+
+#[cfg(test)]
+mod optimiser_tests {
+    use super::*;
+    use crate::generator::{self, GeneratorConfig};
+    use crate::test_fixtures::{example_games, parse_games};
+
+    fn costs_and_scores(frontier: &[BagChoice]) -> Vec<(u64, u64)> {
+        frontier
+            .iter()
+            .map(|choice| (choice.cost, choice.score))
+            .collect()
+    }
+
+    #[test]
+    fn test_game_count_frontier() {
+        let frontier = pareto_frontier(&example_games(), &CubeCost::PER_CUBE, Objective::GameCount);
+
+        assert_eq!(
+            costs_and_scores(&frontier),
+            vec![(0, 0), (8, 1), (13, 2), (15, 3), (32, 4), (48, 5)]
+        );
+        assert_eq!(frontier[3].bag, ColourSet::new(6, 3, 6));
+
+        let best = best_bag(&frontier, 39).unwrap();
+        assert_eq!(best.bag, ColourSet::new(15, 3, 14));
+        assert_eq!(best.score, 4);
+    }
+
+    #[test]
+    fn test_id_sum_frontier() {
+        let frontier = pareto_frontier(&example_games(), &CubeCost::PER_CUBE, Objective::IdSum);
+
+        assert_eq!(
+            costs_and_scores(&frontier),
+            vec![
+                (0, 0),
+                (8, 2),
+                (11, 5),
+                (13, 7),
+                (15, 8),
+                (32, 12),
+                (48, 15)
+            ]
+        );
+        assert_eq!(best_bag(&frontier, 39).unwrap().score, 12);
+        assert_eq!(best_bag(&frontier, 1000).unwrap().score, 15);
+    }
+
+    #[test]
+    fn test_weighted_cost() {
+        let cost = CubeCost {
+            blue: 1,
+            green: 1,
+            red: 10,
+        };
+        let frontier = pareto_frontier(&example_games(), &cost, Objective::GameCount);
+
+        let best = best_bag(&frontier, 40).unwrap();
+        assert_eq!(best.bag, ColourSet::new(4, 3, 1));
+        assert_eq!(best.cost, 17);
+        assert_eq!(best.score, 1);
+    }
+
+    /// Scores every bag built from the counts in the games' minimal sets.
+    fn brute_force_frontier(games: &[Game], objective: Objective) -> Vec<(u64, u64)> {
+        let minimal_sets = games
+            .iter()
+            .map(Game::generate_minimal_set)
+            .collect::<Vec<_>>();
+        let limits = |colour| {
+            minimal_sets
+                .iter()
+                .map(|set| set.count(colour))
+                .chain([0])
+                .collect::<BTreeSet<_>>()
+        };
+
+        let mut choices = Vec::new();
+        for num_blue in limits(Colour::Blue) {
+            for num_green in limits(Colour::Green) {
+                for num_red in limits(Colour::Red) {
+                    let bag = ColourSet::new(num_blue, num_green, num_red);
+                    let admitted = games.iter().filter(|game| game.is_set_valid(&bag));
+                    let score = match objective {
+                        Objective::GameCount => admitted.count() as u64,
+                        Objective::IdSum => admitted.map(|game| game.id as u64).sum(),
+                    };
+                    choices.push((CubeCost::PER_CUBE.of(&bag), score));
+                }
+            }
+        }
+
+        choices.sort_by_key(|(cost, score)| (*cost, std::cmp::Reverse(*score)));
+        let mut frontier: Vec<(u64, u64)> = Vec::new();
+        for (cost, score) in choices {
+            if frontier.last().is_none_or(|best| score > best.1) {
+                frontier.push((cost, score));
+            }
+        }
+        frontier
+    }
+
+    #[test]
+    fn test_frontier_matches_brute_force() {
+        let log = generator::generate(&GeneratorConfig {
+            bag: ColourSet::new(14, 13, 12),
+            games: 100,
+            draws: 4,
+            seed: 3,
+        });
+        let games = parse_games(&log.contents);
+
+        for objective in [Objective::GameCount, Objective::IdSum] {
+            let frontier = pareto_frontier(&games, &CubeCost::PER_CUBE, objective);
+            assert_eq!(
+                costs_and_scores(&frontier),
+                brute_force_frontier(&games, objective)
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_frontier() {
+        let log = generator::generate(&GeneratorConfig {
+            bag: ColourSet::new(14, 13, 12),
+            games: 5000,
+            draws: 6,
+            seed: 1,
+        });
+        let games = parse_games(&log.contents);
+
+        let frontier = pareto_frontier(&games, &CubeCost::PER_CUBE, Objective::GameCount);
+        let all_games = frontier.last().unwrap();
+        assert_eq!(all_games.score, 5000);
+        assert_eq!(
+            all_games.bag,
+            games
+                .iter()
+                .map(Game::generate_minimal_set)
+                .fold(ColourSet::default(), |bag, set| bag | set)
+        );
+        assert!(frontier
+            .windows(2)
+            .all(|pair| pair[0].cost < pair[1].cost && pair[0].score < pair[1].score));
+    }
+
+    #[test]
+    fn test_cost_saturates() {
+        let cost = CubeCost {
+            blue: u64::MAX,
+            green: 1,
+            red: 1,
+        };
+
+        assert_eq!(
+            cost.of(&ColourSet::new(0, u32::MAX, u32::MAX)),
+            2 * u32::MAX as u64
+        );
+        assert_eq!(cost.of(&ColourSet::new(2, 1, 1)), u64::MAX);
+    }
+
+    #[test]
+    fn test_frontier_bags_admit_their_score() {
+        let games = example_games();
+        let frontier = pareto_frontier(&games, &CubeCost::PER_CUBE, Objective::GameCount);
+
+        for choice in frontier {
+            let admitted = games.iter().filter(|game| game.is_set_valid(&choice.bag));
+            assert_eq!(admitted.count() as u64, choice.score);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+mod parser;
+
+use std::fmt;
+
+use crate::game::{Colour, Game, OverflowError};
+
+pub use parser::ParseError;
+
+/// A question asked about a list of [`Game`]s.
+///
+/// Queries are written in a small expression language, for example:
+/// - `red > 10 && max(blue) < 5` lists the IDs of every matching game.
+/// - `sum(power) where id < 50` aggregates a value across the matching games.
+///
+/// Inside a condition, `blue`, `green` and `red` refer to the game's minimal set,
+/// `power` to that set's power, `draws` to the number of subsets drawn and `id` to the game ID.
+/// `sum`, `min`, `max` and `count` of a colour aggregate over the game's draws,
+/// where `count` is the number of draws containing that colour.
+/// The operands of `&&`, `||` and `!` must be conditions and the operands of comparisons
+/// must be numbers, which is checked when the query is parsed.
+#[derive(Debug, PartialEq)]
+pub enum Query {
+    Filter(Expr),
+    Aggregate {
+        aggregate: Aggregate,
+        value: Expr,
+        condition: Option<Expr>,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Expr {
+    Number(u64),
+    Field(Field),
+    Draws(DrawAggregate, Colour),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    Id,
+    Colour(Colour),
+    Power,
+    Draws,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+    Count,
+    Average,
+}
+
+/// An [`Aggregate`] over the draws of a single game, where an average is not supported.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawAggregate {
+    Sum,
+    Min,
+    Max,
+    Count,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompareOp {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum QueryResult {
+    Games(Vec<u32>),
+    Number(u64),
+    Average(f64),
+    /// An aggregate with no value, such as the maximum of no games.
+    Empty,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Query, ParseError> {
+        parser::parse(query)
+    }
+
+    /// Evaluates this query, returning an error if a value is too large to calculate.
+    pub fn evaluate(&self, games: &[Game]) -> Result<QueryResult, OverflowError> {
+        let mut matching = Vec::new();
+        for game in games {
+            let condition = match self {
+                Query::Filter(condition) => Some(condition),
+                Query::Aggregate { condition, .. } => condition.as_ref(),
+            };
+
+            if condition.map_or(Ok(true), |condition| condition.is_true(game))? {
+                matching.push(game);
+            }
+        }
+
+        let (aggregate, value) = match self {
+            Query::Filter(_) => {
+                return Ok(QueryResult::Games(
+                    matching.iter().map(|game| game.id).collect(),
+                ))
+            }
+            Query::Aggregate {
+                aggregate, value, ..
+            } => (aggregate, value),
+        };
+
+        let values = matching
+            .iter()
+            .map(|game| value.number(game))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(match aggregate {
+            Aggregate::Sum => QueryResult::Number(checked_sum(values.iter().copied())?),
+            Aggregate::Count => QueryResult::Number(values.len() as u64),
+            Aggregate::Min => values
+                .iter()
+                .min()
+                .map_or(QueryResult::Empty, |v| QueryResult::Number(*v)),
+            Aggregate::Max => values
+                .iter()
+                .max()
+                .map_or(QueryResult::Empty, |v| QueryResult::Number(*v)),
+            Aggregate::Average if values.is_empty() => QueryResult::Empty,
+            Aggregate::Average => QueryResult::Average(
+                values.iter().map(|v| *v as u128).sum::<u128>() as f64 / values.len() as f64,
+            ),
+        })
+    }
+}
+
+fn checked_sum(mut values: impl Iterator<Item = u64>) -> Result<u64, OverflowError> {
+    values.try_fold(0u64, |sum, value| {
+        sum.checked_add(value)
+            .ok_or(OverflowError { operation: "sum" })
+    })
+}
+
+/// Expressions are type-checked when parsed, so conditions are only ever evaluated with
+/// [`Expr::is_true`] and numbers with [`Expr::number`].
+impl Expr {
+    fn is_true(&self, game: &Game) -> Result<bool, OverflowError> {
+        Ok(match self {
+            Expr::Compare(left, op, right) => {
+                let (left, right) = (left.number(game)?, right.number(game)?);
+                match op {
+                    CompareOp::Less => left < right,
+                    CompareOp::LessEqual => left <= right,
+                    CompareOp::Greater => left > right,
+                    CompareOp::GreaterEqual => left >= right,
+                    CompareOp::Equal => left == right,
+                    CompareOp::NotEqual => left != right,
+                }
+            }
+            Expr::And(left, right) => left.is_true(game)? && right.is_true(game)?,
+            Expr::Or(left, right) => left.is_true(game)? || right.is_true(game)?,
+            Expr::Not(inner) => !inner.is_true(game)?,
+            Expr::Number(_) | Expr::Field(_) | Expr::Draws(..) => {
+                unreachable!("numbers are rejected as conditions when parsing")
+            }
+        })
+    }
+
+    fn number(&self, game: &Game) -> Result<u64, OverflowError> {
+        Ok(match self {
+            Expr::Number(number) => *number,
+            Expr::Field(field) => field.evaluate(game)?,
+            Expr::Draws(aggregate, colour) => {
+                let counts = game
+                    .subsets
+                    .iter()
+                    .map(|subset| subset.count(*colour) as u64);
+                match aggregate {
+                    DrawAggregate::Sum => checked_sum(counts)?,
+                    DrawAggregate::Min => counts.min().unwrap_or(0),
+                    DrawAggregate::Max => counts.max().unwrap_or(0),
+                    DrawAggregate::Count => counts.filter(|count| *count > 0).count() as u64,
+                }
+            }
+            Expr::Compare(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(..) => {
+                unreachable!("conditions are rejected as numbers when parsing")
+            }
+        })
+    }
+
+    /// Returns `true` if this expression evaluates to a condition rather than a number.
+    fn is_condition(&self) -> bool {
+        matches!(
+            self,
+            Expr::Compare(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(..)
+        )
+    }
+}
+
+impl Field {
+    fn evaluate(&self, game: &Game) -> Result<u64, OverflowError> {
+        Ok(match self {
+            Field::Id => game.id as u64,
+            Field::Colour(colour) => game.generate_minimal_set().count(*colour) as u64,
+            Field::Power => game.generate_minimal_set().power()?,
+            Field::Draws => game.subsets.len() as u64,
+        })
+    }
+}
+
+impl fmt::Display for QueryResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryResult::Games(ids) => {
+                let ids = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+                write!(f, "{} games: {}", ids.len(), ids.join(", "))
+            }
+            QueryResult::Number(number) => write!(f, "{}", number),
+            QueryResult::Average(average) => write!(f, "{:.3}", average),
+            QueryResult::Empty => write!(f, "no games matched"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+    use crate::test_fixtures::example_games;
+
+    fn run(query: &str) -> QueryResult {
+        Query::parse(query)
+            .unwrap()
+            .evaluate(&example_games())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_filter() {
+        assert_eq!(run("red > 10"), QueryResult::Games(vec![3, 4]));
+        assert_eq!(
+            run("red > 10 && max(blue) < 10"),
+            QueryResult::Games(vec![3])
+        );
+        assert_eq!(run("id == 1 || id == 5"), QueryResult::Games(vec![1, 5]));
+        assert_eq!(
+            run("!(red <= 12 && green <= 13 && blue <= 14)"),
+            QueryResult::Games(vec![3, 4])
+        );
+        assert_eq!(run("draws = 2"), QueryResult::Games(vec![5]));
+        assert_eq!(run("count(red) >= 3"), QueryResult::Games(vec![3, 4]));
+        assert_eq!(run("sum(blue) > 20"), QueryResult::Games(vec![4]));
+        assert_eq!(run("min(green) > 0"), QueryResult::Games(vec![2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_aggregate() {
+        assert_eq!(run("sum(power)"), QueryResult::Number(2286));
+        assert_eq!(run("sum(power) where id < 3"), QueryResult::Number(48 + 12));
+        assert_eq!(
+            run("sum(id) where red <= 12 && green <= 13 && blue <= 14"),
+            QueryResult::Number(8)
+        );
+        assert_eq!(run("count(id) where red > 10"), QueryResult::Number(2));
+        assert_eq!(run("max(red)"), QueryResult::Number(20));
+        assert_eq!(run("min(power)"), QueryResult::Number(12));
+        assert_eq!(run("avg(draws)"), QueryResult::Average(14.0 / 5.0));
+        assert_eq!(run("max(red) where id > 5"), QueryResult::Empty);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let games = [
+            "Game 1: 4294967295 blue, 4294967295 green, 1 red",
+            "Game 2: 4294967295 blue, 4294967295 green, 1 red",
+        ]
+        .map(Game::parse_from_description);
+
+        let query = Query::parse("sum(power)").unwrap();
+        assert_eq!(
+            query.evaluate(&games),
+            Err(OverflowError { operation: "sum" })
+        );
+
+        let query = Query::parse("max(power)").unwrap();
+        assert_eq!(
+            query.evaluate(&games),
+            Ok(QueryResult::Number(u32::MAX as u64 * u32::MAX as u64))
+        );
+
+        let query = Query::parse("red > 0 && power > 0").unwrap();
+        let games = [Game::parse_from_description(
+            "Game 1: 4294967295 blue, 4294967295 green, 2 red",
+        )];
+        assert_eq!(
+            query.evaluate(&games),
+            Err(OverflowError { operation: "power" })
+        );
+    }
+}
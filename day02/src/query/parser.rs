@@ -0,0 +1,476 @@
+use std::fmt;
+
+use super::{Aggregate, CompareOp, DrawAggregate, Expr, Field, Query};
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    /// Byte offset into the query where the error was found.
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: usize, message: impl Into<String>) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at column {}: {}", self.position + 1, self.message)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(u64),
+    Ident(String),
+    OpenParen,
+    CloseParen,
+    And,
+    Or,
+    Not,
+    Compare(CompareOp),
+    End,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "'{}'", number),
+            Token::Ident(ident) => write!(f, "'{}'", ident),
+            Token::OpenParen => write!(f, "'('"),
+            Token::CloseParen => write!(f, "')'"),
+            Token::And => write!(f, "'&&'"),
+            Token::Or => write!(f, "'||'"),
+            Token::Not => write!(f, "'!'"),
+            Token::Compare(_) => write!(f, "comparison"),
+            Token::End => write!(f, "end of query"),
+        }
+    }
+}
+
+fn tokenise(query: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((position, character)) = chars.next() {
+        let next = chars.peek().map(|(_, next)| *next);
+
+        let token = match (character, next) {
+            (c, _) if c.is_whitespace() => continue,
+            ('(', _) => Token::OpenParen,
+            (')', _) => Token::CloseParen,
+            ('&', Some('&')) => {
+                chars.next();
+                Token::And
+            }
+            ('|', Some('|')) => {
+                chars.next();
+                Token::Or
+            }
+            ('!', Some('=')) => {
+                chars.next();
+                Token::Compare(CompareOp::NotEqual)
+            }
+            ('!', _) => Token::Not,
+            ('<', Some('=')) => {
+                chars.next();
+                Token::Compare(CompareOp::LessEqual)
+            }
+            ('>', Some('=')) => {
+                chars.next();
+                Token::Compare(CompareOp::GreaterEqual)
+            }
+            ('=', Some('=')) => {
+                chars.next();
+                Token::Compare(CompareOp::Equal)
+            }
+            ('<', _) => Token::Compare(CompareOp::Less),
+            ('>', _) => Token::Compare(CompareOp::Greater),
+            ('=', _) => Token::Compare(CompareOp::Equal),
+            (c, _) if c.is_ascii_digit() => {
+                let mut number = c.to_string();
+                while let Some((_, digit)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                    number.push(digit);
+                }
+                let number = number
+                    .parse()
+                    .map_err(|_| ParseError::new(position, "number is too large"))?;
+                Token::Number(number)
+            }
+            (c, _) if c.is_ascii_alphabetic() => {
+                let mut ident = c.to_string();
+                while let Some((_, c)) =
+                    chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    ident.push(c);
+                }
+                Token::Ident(ident.to_ascii_lowercase())
+            }
+            (c, _) => return Err(ParseError::new(position, format!("unexpected '{}'", c))),
+        };
+
+        tokens.push((position, token));
+    }
+
+    tokens.push((query.len(), Token::End));
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+}
+
+/// Parses a [`Query`] using the following grammar:
+///
+/// ```text
+/// query      := aggregate ( "where" condition )? | condition ( "where" condition )?
+/// aggregate  := function "(" expr ")"
+/// expr       := and ( "||" and )*
+/// and        := not ( "&&" not )*
+/// not        := "!" not | comparison
+/// comparison := primary ( ( "<" | "<=" | ">" | ">=" | "==" | "!=" ) primary )?
+/// primary    := number | field | function "(" colour ")" | "(" expr ")"
+/// ```
+pub fn parse(query: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser {
+        tokens: tokenise(query)?,
+        index: 0,
+    };
+
+    let query = if let Some(aggregate) = parser.top_level_aggregate() {
+        parser.index += 2;
+        let value = parser.number()?;
+        parser.expect(Token::CloseParen)?;
+        let condition = parser.where_clause()?;
+
+        Query::Aggregate {
+            aggregate,
+            value,
+            condition,
+        }
+    } else {
+        let condition = parser.condition()?;
+        let condition = match parser.where_clause()? {
+            Some(filter) => Expr::And(Box::new(condition), Box::new(filter)),
+            None => condition,
+        };
+
+        Query::Filter(condition)
+    };
+
+    parser.expect(Token::End)?;
+    Ok(query)
+}
+
+fn aggregate_from_name(name: &str) -> Option<Aggregate> {
+    match name {
+        "sum" => Some(Aggregate::Sum),
+        "min" => Some(Aggregate::Min),
+        "max" => Some(Aggregate::Max),
+        "count" => Some(Aggregate::Count),
+        "avg" => Some(Aggregate::Average),
+        _ => None,
+    }
+}
+
+/// Returns `expr` if it is a condition when `condition` is `true`, or a number otherwise,
+/// reporting a mismatch at `position`, where the expression starts.
+fn check_type(expr: Expr, position: usize, condition: bool) -> Result<Expr, ParseError> {
+    match (expr.is_condition(), condition) {
+        (false, true) => Err(ParseError::new(position, "expected a condition")),
+        (true, false) => Err(ParseError::new(position, "expected a number")),
+        _ => Ok(expr),
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].1
+    }
+
+    fn position(&self) -> usize {
+        self.tokens[self.index].0
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].1.clone();
+        if token != Token::End {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ParseError> {
+        if *self.peek() == expected {
+            self.advance();
+            Ok(())
+        } else {
+            let message = match expected {
+                Token::End => format!("unexpected {}", self.peek()),
+                expected => format!("expected {} but found {}", expected, self.peek()),
+            };
+            Err(ParseError::new(self.position(), message))
+        }
+    }
+
+    /// Returns the aggregate if the query is a single aggregate call over games,
+    /// optionally followed by a `where` clause.
+    fn top_level_aggregate(&self) -> Option<Aggregate> {
+        let Token::Ident(name) = self.peek() else {
+            return None;
+        };
+        let aggregate = aggregate_from_name(name)?;
+        if self.tokens[self.index + 1].1 != Token::OpenParen {
+            return None;
+        }
+
+        let mut depth = 0;
+        for (offset, (_, token)) in self.tokens[self.index + 1..].iter().enumerate() {
+            match token {
+                Token::OpenParen => depth += 1,
+                Token::CloseParen => depth -= 1,
+                _ => {}
+            }
+
+            if depth == 0 {
+                let after = &self.tokens[self.index + offset + 2].1;
+                let is_whole_query =
+                    *after == Token::End || *after == Token::Ident("where".to_string());
+                return is_whole_query.then_some(aggregate);
+            }
+        }
+
+        None
+    }
+
+    fn where_clause(&mut self) -> Result<Option<Expr>, ParseError> {
+        if *self.peek() == Token::Ident("where".to_string()) {
+            self.advance();
+            self.condition().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses an expression, which must be a condition.
+    fn condition(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let expr = self.expr()?;
+        check_type(expr, position, true)
+    }
+
+    /// Parses an expression, which must be a number.
+    fn number(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let expr = self.expr()?;
+        check_type(expr, position, false)
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let mut left = self.and()?;
+        if *self.peek() == Token::Or {
+            left = check_type(left, position, true)?;
+        }
+
+        while *self.peek() == Token::Or {
+            self.advance();
+            let position = self.position();
+            let right = check_type(self.and()?, position, true)?;
+            left = Expr::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let mut left = self.not()?;
+        if *self.peek() == Token::And {
+            left = check_type(left, position, true)?;
+        }
+
+        while *self.peek() == Token::And {
+            self.advance();
+            let position = self.position();
+            let right = check_type(self.not()?, position, true)?;
+            left = Expr::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr, ParseError> {
+        if *self.peek() == Token::Not {
+            self.advance();
+            let position = self.position();
+            let inner = check_type(self.not()?, position, true)?;
+            return Ok(Expr::Not(Box::new(inner)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+        let left = self.primary()?;
+        if let Token::Compare(op) = *self.peek() {
+            let left = check_type(left, position, false)?;
+            self.advance();
+            let position = self.position();
+            let right = check_type(self.primary()?, position, false)?;
+            return Ok(Expr::Compare(Box::new(left), op, Box::new(right)));
+        }
+        Ok(left)
+    }
+
+    fn primary(&mut self) -> Result<Expr, ParseError> {
+        let position = self.position();
+
+        match self.advance() {
+            Token::Number(number) => Ok(Expr::Number(number)),
+            Token::OpenParen => {
+                let expr = self.expr()?;
+                self.expect(Token::CloseParen)?;
+                Ok(expr)
+            }
+            Token::Ident(name) if *self.peek() == Token::OpenParen => {
+                let aggregate = match aggregate_from_name(&name) {
+                    Some(Aggregate::Sum) => DrawAggregate::Sum,
+                    Some(Aggregate::Min) => DrawAggregate::Min,
+                    Some(Aggregate::Max) => DrawAggregate::Max,
+                    Some(Aggregate::Count) => DrawAggregate::Count,
+                    Some(Aggregate::Average) | None => {
+                        return Err(ParseError::new(
+                            position,
+                            format!("unknown function '{}'", name),
+                        ))
+                    }
+                };
+
+                self.advance();
+                let position = self.position();
+                let colour = match self.advance() {
+                    Token::Ident(colour) => colour.parse().ok(),
+                    _ => None,
+                }
+                .ok_or_else(|| ParseError::new(position, "expected a colour"))?;
+                self.expect(Token::CloseParen)?;
+
+                Ok(Expr::Draws(aggregate, colour))
+            }
+            Token::Ident(name) => {
+                let field = match name.as_str() {
+                    "id" => Field::Id,
+                    "power" => Field::Power,
+                    "draws" => Field::Draws,
+                    colour => Field::Colour(colour.parse().map_err(|_| {
+                        ParseError::new(position, format!("unknown field '{}'", name))
+                    })?),
+                };
+                Ok(Expr::Field(field))
+            }
+            token => Err(ParseError::new(position, format!("unexpected {}", token))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod parser_tests {
+    use super::*;
+    use crate::game::Colour;
+
+    #[test]
+    fn test_parse_filter() {
+        let query = parse("red > 10 && max(blue) < 5").unwrap();
+
+        assert_eq!(
+            query,
+            Query::Filter(Expr::And(
+                Box::new(Expr::Compare(
+                    Box::new(Expr::Field(Field::Colour(Colour::Red))),
+                    CompareOp::Greater,
+                    Box::new(Expr::Number(10)),
+                )),
+                Box::new(Expr::Compare(
+                    Box::new(Expr::Draws(DrawAggregate::Max, Colour::Blue)),
+                    CompareOp::Less,
+                    Box::new(Expr::Number(5)),
+                )),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_aggregate() {
+        let query = parse("sum(power) where id < 50").unwrap();
+
+        assert_eq!(
+            query,
+            Query::Aggregate {
+                aggregate: Aggregate::Sum,
+                value: Expr::Field(Field::Power),
+                condition: Some(Expr::Compare(
+                    Box::new(Expr::Field(Field::Id)),
+                    CompareOp::Less,
+                    Box::new(Expr::Number(50)),
+                )),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("red").unwrap_err().message, "expected a condition");
+        assert_eq!(
+            parse("purple > 1").unwrap_err().message,
+            "unknown field 'purple'"
+        );
+        assert_eq!(
+            parse("avg(red) > 1").unwrap_err().message,
+            "unknown function 'avg'"
+        );
+        assert_eq!(
+            parse("max(id) > 1").unwrap_err().message,
+            "expected a colour"
+        );
+        assert_eq!(parse("red > 1 &").unwrap_err().position, 8);
+        assert_eq!(
+            parse("sum(red > 1)").unwrap_err().message,
+            "expected a number"
+        );
+        assert!(parse("(red > 1").is_err());
+        assert!(parse("red > 1 blue").is_err());
+    }
+
+    #[test]
+    fn test_parse_type_errors() {
+        assert_eq!(
+            parse("red > 1 && blue"),
+            Err(ParseError::new(11, "expected a condition"))
+        );
+        assert_eq!(
+            parse("!red"),
+            Err(ParseError::new(1, "expected a condition"))
+        );
+        assert_eq!(
+            parse("(red > 1) > 0"),
+            Err(ParseError::new(0, "expected a number"))
+        );
+        assert_eq!(
+            parse("blue || red > 1"),
+            Err(ParseError::new(0, "expected a condition"))
+        );
+        assert_eq!(
+            parse("red > (blue < 2)"),
+            Err(ParseError::new(6, "expected a number"))
+        );
+        assert_eq!(
+            parse("count(id) where id"),
+            Err(ParseError::new(16, "expected a condition"))
+        );
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::game::{Colour, ColourSet, Game, OverflowError};
+
+/// Summary statistics about a list of [`Game`]s, relative to a bag.
+pub struct Report {
+    /// For each colour, how many draws contained each number of cubes of that colour.
+    pub draw_sizes: [BTreeMap<u32, usize>; 3],
+    pub games: Vec<GameSummary>,
+}
+
+pub struct GameSummary {
+    pub id: u32,
+    pub minimal_set: ColourSet,
+    pub power: u64,
+    /// The fewest cubes of any one colour that could be removed from the bag before
+    /// the game becomes impossible, or how far it is over the bag if negative.
+    pub margin: i64,
+}
+
+/// The spread of the minimal sets' powers.
+#[derive(Debug, PartialEq)]
+pub struct PowerDistribution {
+    pub min: u64,
+    pub lower_quartile: u64,
+    pub median: u64,
+    pub upper_quartile: u64,
+    pub max: u64,
+    pub mean: f64,
+}
+
+impl Report {
+    pub fn new(games: &[Game], bag: &ColourSet) -> Result<Report, OverflowError> {
+        let mut draw_sizes = [BTreeMap::new(), BTreeMap::new(), BTreeMap::new()];
+        for subset in games.iter().flat_map(|game| &game.subsets) {
+            for colour in Colour::ALL {
+                let count = subset.count(colour);
+                if count > 0 {
+                    *draw_sizes[colour.index()].entry(count).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let games = games
+            .iter()
+            .map(|game| {
+                let minimal_set = game.generate_minimal_set();
+                let margin = Colour::ALL
+                    .into_iter()
+                    .map(|colour| bag.count(colour) as i64 - minimal_set.count(colour) as i64)
+                    .min()
+                    .unwrap_or_default();
+
+                Ok(GameSummary {
+                    id: game.id,
+                    minimal_set,
+                    power: minimal_set.power()?,
+                    margin,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Report { draw_sizes, games })
+    }
+
+    /// Returns the distribution of powers, or `None` if there are no games.
+    pub fn power_distribution(&self) -> Option<PowerDistribution> {
+        let mut powers = self.games.iter().map(|game| game.power).collect::<Vec<_>>();
+        powers.sort_unstable();
+
+        let quantile = |q: usize| powers[(powers.len() - 1) * q / 4];
+        let mean =
+            powers.iter().map(|power| *power as u128).sum::<u128>() as f64 / powers.len() as f64;
+
+        (!powers.is_empty()).then(|| PowerDistribution {
+            min: quantile(0),
+            lower_quartile: quantile(1),
+            median: quantile(2),
+            upper_quartile: quantile(3),
+            max: quantile(4),
+            mean,
+        })
+    }
+
+    /// Returns up to `count` possible games with the smallest margin, closest first.
+    pub fn closest_to_invalid(&self, count: usize) -> Vec<&GameSummary> {
+        let mut possible = self
+            .games
+            .iter()
+            .filter(|game| game.margin >= 0)
+            .collect::<Vec<_>>();
+        possible.sort_by_key(|game| (game.margin, game.id));
+        possible.truncate(count);
+        possible
+    }
+
+    /// Writes the report as a single CSV table, with a `section` column naming which part of
+    /// the report each row belongs to: `draw_size`, `game`, `power_distribution` or
+    /// `closest_to_invalid`. Columns that don't apply to a section are left empty.
+    pub fn write_csv(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(f, "{}", CSV_COLUMNS.join(","))?;
+
+        for colour in Colour::ALL {
+            for (size, draws) in &self.draw_sizes[colour.index()] {
+                write_csv_row(
+                    f,
+                    "draw_size",
+                    &[
+                        ("colour", colour.to_string()),
+                        ("draw_size", size.to_string()),
+                        ("draws", draws.to_string()),
+                    ],
+                )?;
+            }
+        }
+
+        for game in &self.games {
+            write_game_row(f, "game", game)?;
+        }
+
+        if let Some(distribution) = self.power_distribution() {
+            for (statistic, value) in [
+                ("min", distribution.min.to_string()),
+                ("lower_quartile", distribution.lower_quartile.to_string()),
+                ("median", distribution.median.to_string()),
+                ("upper_quartile", distribution.upper_quartile.to_string()),
+                ("max", distribution.max.to_string()),
+                ("mean", distribution.mean.to_string()),
+            ] {
+                write_csv_row(
+                    f,
+                    "power_distribution",
+                    &[("statistic", statistic.to_string()), ("value", value)],
+                )?;
+            }
+        }
+
+        for game in self.closest_to_invalid(CLOSEST_TO_INVALID) {
+            write_game_row(f, "closest_to_invalid", game)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How many of the games closest to being impossible are reported.
+const CLOSEST_TO_INVALID: usize = 5;
+
+const CSV_COLUMNS: [&str; 12] = [
+    "section",
+    "game",
+    "colour",
+    "draw_size",
+    "draws",
+    "blue",
+    "green",
+    "red",
+    "power",
+    "margin",
+    "statistic",
+    "value",
+];
+
+/// Writes a row of the CSV table, leaving every column not in `cells` empty.
+fn write_csv_row(f: &mut impl fmt::Write, section: &str, cells: &[(&str, String)]) -> fmt::Result {
+    write!(f, "{}", section)?;
+    for column in &CSV_COLUMNS[1..] {
+        let cell = cells.iter().find(|(name, _)| name == column);
+        write!(f, ",{}", cell.map_or("", |(_, value)| value.as_str()))?;
+    }
+    writeln!(f)
+}
+
+fn write_game_row(f: &mut impl fmt::Write, section: &str, game: &GameSummary) -> fmt::Result {
+    write_csv_row(
+        f,
+        section,
+        &[
+            ("game", game.id.to_string()),
+            ("blue", game.minimal_set.num_blue.to_string()),
+            ("green", game.minimal_set.num_green.to_string()),
+            ("red", game.minimal_set.num_red.to_string()),
+            ("power", game.power.to_string()),
+            ("margin", game.margin.to_string()),
+        ],
+    )
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Draw sizes:")?;
+        writeln!(
+            f,
+            "  {:>5} {:>6} {:>6} {:>6}",
+            "size", "blue", "green", "red"
+        )?;
+        let sizes = self
+            .draw_sizes
+            .iter()
+            .flat_map(|sizes| sizes.keys())
+            .collect::<BTreeSet<_>>();
+        for size in sizes {
+            write!(f, "  {:>5}", size)?;
+            for colour in Colour::ALL {
+                let draws = self.draw_sizes[colour.index()].get(size).unwrap_or(&0);
+                write!(f, " {:>6}", draws)?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Minimal sets:")?;
+        for game in &self.games {
+            writeln!(
+                f,
+                "  Game {}: {} (power {})",
+                game.id, game.minimal_set, game.power
+            )?;
+        }
+
+        if let Some(distribution) = self.power_distribution() {
+            writeln!(f)?;
+            writeln!(f, "Power distribution:")?;
+            writeln!(
+                f,
+                "  min {}, lower quartile {}, median {}, upper quartile {}, max {}, mean {:.1}",
+                distribution.min,
+                distribution.lower_quartile,
+                distribution.median,
+                distribution.upper_quartile,
+                distribution.max,
+                distribution.mean
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Possible games closest to being impossible:")?;
+        for game in self.closest_to_invalid(CLOSEST_TO_INVALID) {
+            writeln!(f, "  Game {}: margin {}", game.id, game.margin)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod report_tests {
+    use super::*;
+    use crate::test_fixtures::example_games;
+
+    fn report() -> Report {
+        Report::new(&example_games(), &ColourSet::new(14, 13, 12)).unwrap()
+    }
+
+    #[test]
+    fn test_draw_sizes() {
+        let report = report();
+
+        let red = &report.draw_sizes[Colour::Red.index()];
+        assert_eq!(red.get(&1), Some(&4));
+        assert_eq!(red.get(&20), Some(&1));
+        assert_eq!(red.values().sum::<usize>(), 11);
+
+        let blue = &report.draw_sizes[Colour::Blue.index()];
+        assert_eq!(blue.get(&6), Some(&3));
+    }
+
+    #[test]
+    fn test_game_summaries() {
+        let report = report();
+
+        let powers = report
+            .games
+            .iter()
+            .map(|game| game.power)
+            .collect::<Vec<_>>();
+        assert_eq!(powers, vec![48, 12, 1560, 630, 36]);
+
+        let margins = report
+            .games
+            .iter()
+            .map(|game| game.margin)
+            .collect::<Vec<_>>();
+        assert_eq!(margins, vec![8, 10, -8, -2, 6]);
+    }
+
+    #[test]
+    fn test_power_distribution() {
+        assert_eq!(
+            report().power_distribution(),
+            Some(PowerDistribution {
+                min: 12,
+                lower_quartile: 36,
+                median: 48,
+                upper_quartile: 630,
+                max: 1560,
+                mean: 2286.0 / 5.0,
+            })
+        );
+
+        let empty = Report::new(&[], &ColourSet::default()).unwrap();
+        assert_eq!(empty.power_distribution(), None);
+    }
+
+    #[test]
+    fn test_closest_to_invalid() {
+        let report = report();
+
+        let closest = report
+            .closest_to_invalid(2)
+            .iter()
+            .map(|game| game.id)
+            .collect::<Vec<_>>();
+        assert_eq!(closest, vec![5, 1]);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut csv = String::new();
+        report().write_csv(&mut csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines[0],
+            "section,game,colour,draw_size,draws,blue,green,red,power,margin,statistic,value"
+        );
+        assert!(lines.iter().all(|line| line.split(',').count() == 12));
+        assert_eq!(lines[1], "draw_size,,blue,1,3,,,,,,,");
+
+        let section = |name: &str| {
+            lines
+                .iter()
+                .filter(|line| line.split(',').next() == Some(name))
+                .copied()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            section("game")[..2],
+            ["game,1,,,,6,2,4,48,8,,", "game,2,,,,4,3,1,12,10,,"]
+        );
+        assert_eq!(section("power_distribution").len(), 6);
+        assert_eq!(
+            section("power_distribution")[2],
+            "power_distribution,,,,,,,,,,median,48"
+        );
+        assert_eq!(
+            section("closest_to_invalid"),
+            [
+                "closest_to_invalid,5,,,,2,3,6,36,6,,",
+                "closest_to_invalid,1,,,,6,2,4,48,8,,",
+                "closest_to_invalid,2,,,,4,3,1,12,10,,"
+            ]
+        );
+    }
+}
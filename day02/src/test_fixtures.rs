@@ -0,0 +1,18 @@
+use crate::game::Game;
+
+/// The example game log from the puzzle description.
+pub const GAME_CONTENTS: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
+
+/// Parses a game log in the puzzle's text format, which is known to be valid.
+pub fn parse_games(contents: &str) -> Vec<Game> {
+    contents.lines().map(Game::parse_from_description).collect()
+}
+
+/// Returns the games in [`GAME_CONTENTS`].
+pub fn example_games() -> Vec<Game> {
+    parse_games(GAME_CONTENTS)
+}
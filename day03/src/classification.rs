@@ -0,0 +1,211 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// What a single character of a schematic represents.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CellClass {
+    Blank,
+    Digit,
+    Symbol,
+}
+
+/// A set of characters, given either by listing them or by a test.
+#[derive(Clone, Debug)]
+pub enum CharacterSet {
+    Only(BTreeSet<char>),
+    Matching(fn(char) -> bool),
+}
+
+impl CharacterSet {
+    pub fn contains(&self, character: char) -> bool {
+        match self {
+            CharacterSet::Only(characters) => characters.contains(&character),
+            CharacterSet::Matching(predicate) => predicate(character),
+        }
+    }
+
+    /// Parses a named set (`any`, `punctuation` or `non-alphanumeric`), or otherwise a list of
+    /// characters where `\s` is a space, `\t` is a tab and `\\` is a backslash.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "any" => return Ok(CharacterSet::Matching(|_| true)),
+            "punctuation" => return Ok(CharacterSet::Matching(|c| c.is_ascii_punctuation())),
+            "non-alphanumeric" => {
+                return Ok(CharacterSet::Matching(|c| {
+                    !c.is_alphanumeric() && !c.is_whitespace()
+                }))
+            }
+            _ => {}
+        }
+
+        let mut characters = BTreeSet::new();
+        let mut chars = s.chars();
+        while let Some(character) = chars.next() {
+            if character != '\\' {
+                characters.insert(character);
+                continue;
+            }
+
+            characters.insert(match chars.next() {
+                Some('s') => ' ',
+                Some('t') => '\t',
+                Some('\\') => '\\',
+                Some(other) => return Err(format!("unknown escape '\\{}'", other)),
+                None => return Err("expected a character after '\\'".to_string()),
+            });
+        }
+
+        Ok(CharacterSet::Only(characters))
+    }
+}
+
+/// Decides which characters of a schematic are blank, digits or symbols.
+/// Characters are checked against the digits, then the blanks, then the symbols,
+/// and any character in none of them is treated as blank.
+#[derive(Clone, Debug)]
+pub struct Classification {
+    /// Which ASCII digits make up numbers. Other characters are never digits.
+    pub digits: CharacterSet,
+    pub blanks: CharacterSet,
+    pub symbols: CharacterSet,
+}
+
+impl Default for Classification {
+    /// The puzzle's classification: '.' is blank, and anything other than a digit is a symbol.
+    fn default() -> Self {
+        Self {
+            digits: CharacterSet::Matching(|c| c.is_ascii_digit()),
+            blanks: CharacterSet::Only(BTreeSet::from(['.'])),
+            symbols: CharacterSet::Matching(|_| true),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid classification on line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+impl Classification {
+    pub fn classify(&self, character: char) -> CellClass {
+        if character.is_ascii_digit() && self.digits.contains(character) {
+            CellClass::Digit
+        } else if self.blanks.contains(character) {
+            CellClass::Blank
+        } else if self.symbols.contains(character) {
+            CellClass::Symbol
+        } else {
+            CellClass::Blank
+        }
+    }
+
+    pub fn is_digit(&self, character: char) -> bool {
+        self.classify(character) == CellClass::Digit
+    }
+
+    pub fn is_symbol(&self, character: char) -> bool {
+        self.classify(character) == CellClass::Symbol
+    }
+
+    /// Parses a classification from lines of `<digits|blanks|symbols> = <characters>`,
+    /// where the characters are as accepted by [`CharacterSet::parse`].
+    /// Blank lines and lines starting with '#' are ignored, and unset keys keep their defaults.
+    /// Example: `blanks = .\s` then `symbols = punctuation`
+    pub fn parse(contents: &str) -> Result<Self, ParseError> {
+        let mut classification = Classification::default();
+
+        for (index, line) in contents.lines().enumerate() {
+            let error = |message: String| ParseError {
+                line: index + 1,
+                message,
+            };
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected '<key> = <characters>'".to_string()))?;
+            let set = CharacterSet::parse(value.trim()).map_err(error)?;
+
+            match key.trim() {
+                "digits" => classification.digits = set,
+                "blanks" => classification.blanks = set,
+                "symbols" => classification.symbols = set,
+                key => return Err(error(format!("unknown key '{}'", key))),
+            }
+        }
+
+        Ok(classification)
+    }
+}
+
+#[cfg(test)]
+mod classification_tests {
+    use super::*;
+
+    #[test]
+    fn test_default_classification() {
+        let classification = Classification::default();
+
+        assert_eq!(classification.classify('7'), CellClass::Digit);
+        assert_eq!(classification.classify('.'), CellClass::Blank);
+        assert_eq!(classification.classify('#'), CellClass::Symbol);
+        assert_eq!(classification.classify(' '), CellClass::Symbol);
+        assert_eq!(classification.classify('a'), CellClass::Symbol);
+    }
+
+    #[test]
+    fn test_parse() {
+        let classification = Classification::parse(
+            "# Exported by another tool\nblanks = .\\s\n\nsymbols = punctuation",
+        )
+        .unwrap();
+
+        assert_eq!(classification.classify('7'), CellClass::Digit);
+        assert_eq!(classification.classify(' '), CellClass::Blank);
+        assert_eq!(classification.classify('.'), CellClass::Blank);
+        assert_eq!(classification.classify('#'), CellClass::Symbol);
+        // Letters are neither blanks nor symbols, so they're treated as blank
+        assert_eq!(classification.classify('a'), CellClass::Blank);
+
+        let classification = Classification::parse("digits = 123\nsymbols = *\\\\").unwrap();
+        assert_eq!(classification.classify('0'), CellClass::Blank);
+        assert_eq!(classification.classify('\\'), CellClass::Symbol);
+        assert_eq!(classification.classify('#'), CellClass::Blank);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Classification::parse("blanks = .\nsymbols").unwrap_err(),
+            ParseError {
+                line: 2,
+                message: "expected '<key> = <characters>'".to_string()
+            }
+        );
+        assert_eq!(
+            Classification::parse("letters = abc")
+                .unwrap_err()
+                .to_string(),
+            "invalid classification on line 1: unknown key 'letters'"
+        );
+        assert_eq!(
+            Classification::parse("blanks = \\q").unwrap_err().message,
+            "unknown escape '\\q'"
+        );
+    }
+}
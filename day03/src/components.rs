@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use grid::{Coord, Grid};
+
+use crate::schematic::{Number, OverflowError, Schematic, Symbol};
+
+/// A group of numbers and symbols that touch each other, directly or through other members.
+#[derive(Debug)]
+pub struct Component {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    /// The top-left corner of the smallest rectangle containing the component.
+    pub top_left: Coord,
+    /// The bottom-right corner of the smallest rectangle containing the component.
+    pub bottom_right: Coord,
+}
+
+impl Component {
+    /// The number of numbers and symbols in the component.
+    pub fn size(&self) -> usize {
+        self.numbers.len() + self.symbols.len()
+    }
+
+    /// The sum of the component's part numbers, i.e. those adjacent to a symbol.
+    pub fn total_value(&self) -> usize {
+        self.numbers
+            .iter()
+            .filter(|number| number.is_part_number())
+            .map(Number::value)
+            .sum()
+    }
+}
+
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} members ({} numbers, {} symbols) with a total value of {}, from {} to {}",
+            self.size(),
+            self.numbers.len(),
+            self.symbols.len(),
+            self.total_value(),
+            self.top_left,
+            self.bottom_right
+        )
+    }
+}
+
+/// A union-find structure over the indexes `0..len`.
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+
+        // Point everything on the path directly at the root, so later finds are faster
+        let mut index = index;
+        while self.parents[index] != root {
+            let parent = self.parents[index];
+            self.parents[index] = root;
+            index = parent;
+        }
+
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+
+        // Attach the smaller tree to the larger one to keep the trees shallow
+        let (root, child) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[child] = root;
+        self.sizes[root] += self.sizes[child];
+    }
+}
+
+/// Groups the schematic's numbers and symbols into components, where any two that are
+/// adjacent are in the same component. Components are ordered by their first cell.
+pub fn find_components(schematic: &Schematic) -> Result<Vec<Component>, OverflowError> {
+    let grid = schematic.grid();
+    let numbers = schematic.get_numbers()?;
+    let symbols = schematic.get_symbols();
+
+    // Give each number and symbol an index, and record which one covers each cell
+    let mut members: Grid<Option<usize>> = Grid::new(
+        grid.width(),
+        grid.height(),
+        vec![None; grid.width() * grid.height()],
+    );
+    for (index, number) in numbers.iter().enumerate() {
+        for column in number.start_column()..=number.end_column() {
+            members[Coord::new(number.row(), column)] = Some(index);
+        }
+    }
+    for (index, symbol) in symbols.iter().enumerate() {
+        members[symbol.coord()] = Some(numbers.len() + index);
+    }
+
+    let mut disjoint_set = DisjointSet::new(numbers.len() + symbols.len());
+    for (coord, member) in members.iter() {
+        let Some(member) = member else {
+            continue;
+        };
+
+        for neighbour in grid.neighbours(coord, schematic.neighbourhood()) {
+            if let Some(other) = members[neighbour] {
+                disjoint_set.union(*member, other);
+            }
+        }
+    }
+
+    // Collect the members of each component, in the order each component is first seen
+    let mut components: Vec<Component> = Vec::new();
+    let mut component_indexes = HashMap::new();
+    for (coord, member) in members.iter() {
+        let Some(member) = member else {
+            continue;
+        };
+
+        let root = disjoint_set.find(*member);
+        let index = *component_indexes.entry(root).or_insert_with(|| {
+            components.push(Component {
+                numbers: Vec::new(),
+                symbols: Vec::new(),
+                top_left: coord,
+                bottom_right: coord,
+            });
+            components.len() - 1
+        });
+
+        let component = &mut components[index];
+        component.top_left.row = component.top_left.row.min(coord.row);
+        component.top_left.column = component.top_left.column.min(coord.column);
+        component.bottom_right.row = component.bottom_right.row.max(coord.row);
+        component.bottom_right.column = component.bottom_right.column.max(coord.column);
+
+        // Only add each number once, from its first digit
+        match numbers.get(*member) {
+            Some(number) if number.start_column() == coord.column => {
+                component.numbers.push(number.clone())
+            }
+            Some(_) => {}
+            None => component.symbols.push(symbols[*member - numbers.len()]),
+        }
+    }
+
+    Ok(components)
+}
+
+#[cfg(test)]
+mod components_tests {
+    use super::*;
+    use crate::schematic::EXAMPLE;
+
+    #[test]
+    fn test_find_components() {
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
+        let components = find_components(&schematic).unwrap();
+
+        let summaries = components
+            .iter()
+            .map(|component| (component.size(), component.total_value()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summaries,
+            vec![
+                (3, 467 + 35),
+                (1, 0),
+                (2, 633),
+                (2, 617),
+                (2, 592),
+                (1, 0),
+                (3, 755 + 598),
+                (2, 664)
+            ]
+        );
+
+        let machine = &components[6];
+        assert_eq!(machine.top_left, Coord::new(7, 5));
+        assert_eq!(machine.bottom_right, Coord::new(9, 8));
+        assert_eq!(machine.symbols, vec![Symbol::new('*', Coord::new(8, 5))]);
+    }
+
+    #[test]
+    fn test_touching_numbers() {
+        // The numbers touch diagonally, and the symbol joins the third
+        let schematic = Schematic::parse_from_contents("12...\n..3.#\n....4");
+        let components = find_components(&schematic).unwrap();
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].numbers.len(), 2);
+        assert_eq!(components[0].total_value(), 0);
+        assert_eq!(components[1].size(), 2);
+        assert_eq!(components[1].total_value(), 4);
+        assert_eq!(
+            components[1].to_string(),
+            "2 members (1 numbers, 1 symbols) with a total value of 4, from (1, 4) to (2, 4)"
+        );
+    }
+}
@@ -0,0 +1,408 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use grid::Coord;
+
+use crate::gear::{Gear, GearRule};
+use crate::schematic::{Number, OverflowError, PartNumber, Schematic, Symbol};
+
+/// Identifies a row for as long as it is in the schematic, wherever it moves to as rows are
+/// inserted or removed before it.
+type RowId = usize;
+
+/// A cell, given by its row's ID and its column.
+type Cell = (RowId, usize);
+
+/// A number found on a row, with the cells of its symbols given by row ID.
+struct RowNumber {
+    value: usize,
+    start_column: usize,
+    end_column: usize,
+    symbols: Vec<(Cell, char)>,
+}
+
+/// A schematic that can be edited, keeping its part numbers and gears up to date by only
+/// rescanning the rows within reach of each change. Everything found is keyed by row ID rather
+/// than position, so inserting or removing a row doesn't change what is known about the rows after it.
+///
+/// An edit can leave a number too large for a `usize`, such as while digits are being typed
+/// between two numbers. Its row then counts as having no numbers, and [`Self::part_numbers`] and
+/// [`Self::gears`] return an error until a later edit makes the number small enough again.
+pub struct IncrementalSchematic {
+    schematic: Schematic,
+    gear_rule: GearRule,
+    /// The ID of each row, from top to bottom.
+    row_ids: Vec<RowId>,
+    next_row_id: RowId,
+    /// Every number on each row, including those with no adjacent symbols.
+    rows: Vec<Vec<RowNumber>>,
+    /// The values of the part numbers touching each gear symbol, keyed by their first digit.
+    gear_parts: HashMap<Cell, BTreeMap<Cell, usize>>,
+    /// The first digit of the number that is too large on each row that has one.
+    overflows: BTreeMap<RowId, usize>,
+}
+
+impl IncrementalSchematic {
+    pub fn new(schematic: Schematic, gear_rule: GearRule) -> Self {
+        let height = schematic.grid().height();
+
+        let mut incremental = Self {
+            schematic,
+            gear_rule,
+            row_ids: (0..height).collect(),
+            next_row_id: height,
+            rows: (0..height).map(|_| Vec::new()).collect(),
+            gear_parts: HashMap::new(),
+            overflows: BTreeMap::new(),
+        };
+        incremental.rescan((0..height).collect());
+        incremental
+    }
+
+    pub fn schematic(&self) -> &Schematic {
+        &self.schematic
+    }
+
+    /// Returns the part numbers in the same order as [`Schematic::get_part_numbers`],
+    /// or an error for the first number that is too large.
+    pub fn part_numbers(&self) -> Result<Vec<PartNumber>, OverflowError> {
+        let positions = self.row_positions();
+        self.check_overflows(&positions)?;
+
+        Ok(self
+            .rows
+            .iter()
+            .enumerate()
+            .flat_map(|(row, numbers)| numbers.iter().map(move |number| (row, number)))
+            .filter_map(move |(row, number)| {
+                let symbols = number
+                    .symbols
+                    .iter()
+                    .map(|((row_id, column), character)| {
+                        Symbol::new(*character, Coord::new(positions[row_id], *column))
+                    })
+                    .collect();
+                let number = Number::new(
+                    number.value,
+                    row,
+                    number.start_column,
+                    number.end_column,
+                    symbols,
+                );
+
+                PartNumber::try_from(number).ok()
+            })
+            .collect())
+    }
+
+    /// Returns the gears, ordered by position, or an error for the first number or gear that is too large.
+    pub fn gears(&self) -> Result<Vec<Gear>, OverflowError> {
+        let positions = self.row_positions();
+        self.check_overflows(&positions)?;
+
+        let mut gears = self
+            .gear_parts
+            .iter()
+            .filter(|(_, parts)| self.gear_rule.parts.contains(&parts.len()))
+            .map(|((row_id, column), parts)| (Coord::new(positions[row_id], *column), parts))
+            .collect::<Vec<_>>();
+        gears.sort_by_key(|(coord, _)| *coord);
+
+        gears
+            .into_iter()
+            .map(|(coord, parts)| {
+                Ok(Gear {
+                    coord,
+                    character: self.schematic.grid()[coord],
+                    value: self
+                        .gear_rule
+                        .combine
+                        .apply(parts.values().copied())
+                        .ok_or(OverflowError::Gear(coord))?,
+                })
+            })
+            .collect()
+    }
+
+    /// Returns an error for the first number that is too large, if there are any.
+    fn check_overflows(&self, positions: &HashMap<RowId, usize>) -> Result<(), OverflowError> {
+        match self
+            .overflows
+            .iter()
+            .map(|(row_id, column)| Coord::new(positions[row_id], *column))
+            .min()
+        {
+            Some(coord) => Err(OverflowError::Number(coord)),
+            None => Ok(()),
+        }
+    }
+
+    /// Replaces the character in a cell, returning the character it replaced.
+    ///
+    /// # Panics
+    /// Panics if the cell is outside the schematic.
+    pub fn set_cell(&mut self, coord: Coord, character: char) -> char {
+        let replaced = self.schematic.set_cell(coord, character);
+        self.rescan(self.rows_within_reach(coord.row));
+        replaced
+    }
+
+    /// Inserts a row before the row at `index`, padding it with blank cells to the schematic's width.
+    ///
+    /// # Panics
+    /// Panics if the row is wider than the schematic, or `index` is past the last row.
+    pub fn insert_row(&mut self, index: usize, row: &str) {
+        self.schematic.insert_row(index, row);
+        self.row_ids.insert(index, self.next_row_id);
+        self.next_row_id += 1;
+        self.rows.insert(index, Vec::new());
+
+        self.rescan(self.rows_within_reach(index));
+    }
+
+    /// Removes the row at `index`.
+    ///
+    /// # Panics
+    /// Panics if the row is outside the schematic.
+    pub fn remove_row(&mut self, index: usize) {
+        self.schematic.remove_row(index);
+        let row_id = self.row_ids.remove(index);
+        let numbers = self.rows.remove(index);
+        self.remove_gear_parts(row_id, &numbers);
+        self.overflows.remove(&row_id);
+
+        // The rows either side of the removed row are now adjacent, and any numbers that
+        // touched a symbol on the removed row are within reach of one of them
+        let mut rows = self.rows_within_reach(index);
+        if let Some(above) = index.checked_sub(1) {
+            rows.extend(self.rows_within_reach(above));
+        }
+        self.rescan(rows);
+    }
+
+    /// Returns the position of each row, by its ID.
+    fn row_positions(&self) -> HashMap<RowId, usize> {
+        self.row_ids
+            .iter()
+            .enumerate()
+            .map(|(row, row_id)| (*row_id, row))
+            .collect()
+    }
+
+    /// Returns the rows with cells that can be adjacent to a cell on the given row.
+    fn rows_within_reach(&self, row: usize) -> BTreeSet<usize> {
+        let height = self.rows.len();
+        let neighbourhood = self.schematic.neighbourhood();
+        let reach = neighbourhood.radius();
+
+        (row.saturating_sub(reach)..=row.saturating_add(reach))
+            .filter(|row| *row < height)
+            .chain(
+                // Rows past the top or bottom edge wrap around to the other edge
+                (1..=reach)
+                    .filter(|_| neighbourhood.wrap && height > 0)
+                    .flat_map(|offset| {
+                        let row = row as isize;
+                        let offset = offset as isize;
+                        [row - offset, row + offset]
+                    })
+                    .map(|row| row.rem_euclid(height.max(1) as isize) as usize),
+            )
+            .collect()
+    }
+
+    /// Finds the numbers on each of the rows again, updating the gears they touch.
+    fn rescan(&mut self, rows: BTreeSet<usize>) {
+        for row in rows {
+            let row_id = self.row_ids[row];
+            let numbers = match self.schematic.get_numbers_in_row(row) {
+                Ok(numbers) => {
+                    self.overflows.remove(&row_id);
+                    numbers
+                }
+                Err(err) => {
+                    self.overflows.insert(row_id, err.coord().column);
+                    Vec::new()
+                }
+            };
+
+            let numbers = numbers
+                .into_iter()
+                .map(|number| RowNumber {
+                    value: number.value(),
+                    start_column: number.start_column(),
+                    end_column: number.end_column(),
+                    symbols: number
+                        .symbols()
+                        .iter()
+                        .map(|symbol| {
+                            let coord = symbol.coord();
+                            ((self.row_ids[coord.row], coord.column), symbol.character())
+                        })
+                        .collect(),
+                })
+                .collect::<Vec<_>>();
+
+            let previous = std::mem::take(&mut self.rows[row]);
+            self.remove_gear_parts(row_id, &previous);
+            self.add_gear_parts(row_id, &numbers);
+            self.rows[row] = numbers;
+        }
+    }
+
+    fn add_gear_parts(&mut self, row_id: RowId, numbers: &[RowNumber]) {
+        for number in numbers {
+            for (cell, _) in self.gear_symbols(number) {
+                self.gear_parts
+                    .entry(*cell)
+                    .or_default()
+                    .insert((row_id, number.start_column), number.value);
+            }
+        }
+    }
+
+    fn remove_gear_parts(&mut self, row_id: RowId, numbers: &[RowNumber]) {
+        for number in numbers {
+            for (cell, _) in self.gear_symbols(number) {
+                if let Some(parts) = self.gear_parts.get_mut(cell) {
+                    parts.remove(&(row_id, number.start_column));
+                    if parts.is_empty() {
+                        self.gear_parts.remove(cell);
+                    }
+                }
+            }
+        }
+    }
+
+    fn gear_symbols<'a>(&self, number: &'a RowNumber) -> Vec<&'a (Cell, char)> {
+        number
+            .symbols
+            .iter()
+            .filter(|(_, character)| self.gear_rule.is_gear_symbol(*character))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod editor_tests {
+    use super::*;
+    use crate::schematic::EXAMPLE;
+    use grid::{Adjacency, Neighbourhood};
+    use proptest::prelude::*;
+    use proptest::sample::Index;
+
+    /// Checks the incremental results against finding everything again from scratch.
+    fn assert_matches_full_scan(incremental: &IncrementalSchematic) {
+        let schematic = Schematic::new(incremental.schematic().grid().clone())
+            .with_neighbourhood(*incremental.schematic().neighbourhood());
+        let part_numbers = schematic.get_part_numbers();
+        let gears = part_numbers.clone().and_then(|part_numbers| {
+            let mut gears = Schematic::get_gears(&part_numbers, &incremental.gear_rule)?;
+            gears.sort_by_key(|gear| gear.coord);
+            Ok(gears)
+        });
+
+        assert_eq!(incremental.part_numbers(), part_numbers);
+        assert_eq!(incremental.gears(), gears);
+    }
+
+    #[test]
+    fn test_edits() {
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
+        let mut incremental = IncrementalSchematic::new(schematic, GearRule::default());
+        assert_eq!(incremental.part_numbers().unwrap().len(), 8);
+
+        // Removing the '#' leaves 633 without a symbol
+        assert_eq!(incremental.set_cell(Coord::new(3, 7), '.'), '#');
+        assert_eq!(incremental.part_numbers().unwrap().len(), 7);
+
+        // Separating 755 from the gear below it
+        incremental.insert_row(8, "");
+        assert_eq!(incremental.gears().unwrap().len(), 1);
+        assert_matches_full_scan(&incremental);
+
+        incremental.remove_row(8);
+        assert_eq!(incremental.gears().unwrap().len(), 2);
+        assert_matches_full_scan(&incremental);
+    }
+
+    #[test]
+    fn test_overflowing_edits() {
+        let schematic = Schematic::parse_from_contents("9999999999.9999999999*1");
+        let mut incremental = IncrementalSchematic::new(schematic, GearRule::default());
+        assert_eq!(incremental.gears().unwrap().len(), 1);
+
+        // Joining the two numbers makes one number too large, until they are split again
+        incremental.set_cell(Coord::new(0, 10), '9');
+        assert_eq!(
+            incremental.part_numbers(),
+            Err(OverflowError::Number(Coord::new(0, 0)))
+        );
+        assert_eq!(
+            incremental.gears(),
+            Err(OverflowError::Number(Coord::new(0, 0)))
+        );
+
+        incremental.set_cell(Coord::new(0, 10), '#');
+        assert_eq!(incremental.part_numbers().unwrap().len(), 3);
+        assert_matches_full_scan(&incremental);
+    }
+
+    #[derive(Clone, Debug)]
+    enum Edit {
+        SetCell(Index, Index, char),
+        InsertRow(Index, String),
+        RemoveRow(Index),
+    }
+
+    fn cell() -> impl Strategy<Value = char> {
+        prop::sample::select(vec!['.', '.', '.', '1', '2', '3', '*', '*', '#'])
+    }
+
+    fn edit() -> impl Strategy<Value = Edit> {
+        prop_oneof![
+            4 => (any::<Index>(), any::<Index>(), cell())
+                .prop_map(|(row, column, character)| Edit::SetCell(row, column, character)),
+            1 => (any::<Index>(), prop::collection::vec(cell(), 6))
+                .prop_map(|(row, cells)| Edit::InsertRow(row, cells.into_iter().collect())),
+            1 => any::<Index>().prop_map(Edit::RemoveRow),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_edits_match_full_scan(
+            rows in prop::collection::vec(prop::collection::vec(cell(), 6), 1..8),
+            edits in prop::collection::vec(edit(), 1..20),
+            adjacency in prop::sample::select(vec![
+                Adjacency::VonNeumann,
+                Adjacency::Moore,
+                Adjacency::MooreRadius(2),
+            ]),
+            wrap in any::<bool>(),
+        ) {
+            let contents = rows
+                .iter()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let schematic = Schematic::parse_from_contents(&contents)
+                .with_neighbourhood(Neighbourhood::new(adjacency, wrap));
+            let mut incremental = IncrementalSchematic::new(schematic, GearRule::default());
+
+            for edit in edits {
+                let height = incremental.schematic().grid().height();
+                match edit {
+                    Edit::SetCell(row, column, character) if height > 0 => {
+                        let coord = Coord::new(row.index(height), column.index(6));
+                        incremental.set_cell(coord, character);
+                    }
+                    Edit::InsertRow(row, cells) => incremental.insert_row(row.index(height + 1), &cells),
+                    Edit::RemoveRow(row) if height > 0 => incremental.remove_row(row.index(height)),
+                    _ => {}
+                }
+
+                assert_matches_full_scan(&incremental);
+            }
+        }
+    }
+}
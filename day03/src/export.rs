@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use grid::Coord;
+
+use crate::gear::{Combine, Gear, GearRule};
+use crate::schematic::{CellKind, OverflowError, PartNumber, Schematic};
+
+/// The width and height of each cell, in pixels.
+const CELL_SIZE: usize = 20;
+
+const STYLE: &str = "\
+rect { stroke: #ddd; }
+.blank { fill: #fff; }
+.part { fill: #c8f7c5; }
+.other { fill: #f7c5c5; }
+.symbol { fill: #f7eec5; }
+.gear { fill: #e0c5f7; }
+.outline { fill: transparent; stroke: #27ae60; stroke-width: 2; }
+.gear-outline { fill: transparent; stroke: #8e44ad; stroke-width: 2; }
+.outline:hover, .gear-outline:hover { fill: rgba(0, 0, 0, 0.1); }
+line { stroke: #8e44ad; stroke-width: 2; pointer-events: none; }
+text { text-anchor: middle; dominant-baseline: central; pointer-events: none; }";
+
+/// Draws the schematic as a standalone SVG image. Part numbers and gears are outlined,
+/// each gear is joined to its parts by lines, and hovering over them shows their values.
+pub fn to_svg(schematic: &Schematic, gear_rule: &GearRule) -> Result<String, OverflowError> {
+    let part_numbers = schematic.get_part_numbers()?;
+    let gears = Schematic::get_gears(&part_numbers, gear_rule)?;
+
+    Ok(draw_svg(schematic, gear_rule, &part_numbers, &gears))
+}
+
+fn draw_svg(
+    schematic: &Schematic,
+    gear_rule: &GearRule,
+    part_numbers: &[PartNumber],
+    gears: &[Gear],
+) -> String {
+    let grid = schematic.grid();
+    let kinds = schematic.classify(part_numbers, gears);
+
+    let mut gear_parts: HashMap<Coord, Vec<&PartNumber>> = HashMap::new();
+    for part_number in part_numbers {
+        for symbol in part_number.symbols() {
+            gear_parts
+                .entry(symbol.coord())
+                .or_default()
+                .push(part_number);
+        }
+    }
+
+    let (width, height) = (grid.width() * CELL_SIZE, grid.height() * CELL_SIZE);
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace" font-size="14">"#
+    );
+    let _ = writeln!(svg, "<style>\n{}\n</style>", STYLE);
+
+    for (coord, kind) in kinds.iter() {
+        let (x, y) = corner(coord);
+        let class = match kind {
+            CellKind::Blank => "blank",
+            CellKind::PartNumber => "part",
+            CellKind::OtherNumber => "other",
+            CellKind::Symbol => "symbol",
+            CellKind::Gear => "gear",
+        };
+        let _ = writeln!(
+            svg,
+            r#"<rect class="{class}" x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}"/>"#
+        );
+    }
+
+    for (coord, character) in grid
+        .iter()
+        .filter(|(coord, _)| kinds[*coord] != CellKind::Blank)
+    {
+        let (x, y) = centre(coord);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x}" y="{y}">{}</text>"#,
+            escape(*character)
+        );
+    }
+
+    for gear in gears {
+        let (gear_x, gear_y) = centre(gear.coord);
+        for part_number in &gear_parts[&gear.coord] {
+            let (x, y) = span_centre(part_number);
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{gear_x}" y1="{gear_y}" x2="{x}" y2="{y}"/>"#
+            );
+        }
+    }
+
+    for part_number in part_numbers {
+        let (x, y) = corner(Coord::new(part_number.row(), part_number.start_column()));
+        let width = (part_number.end_column() - part_number.start_column() + 1) * CELL_SIZE;
+        let _ = writeln!(
+            svg,
+            r#"<rect class="outline" x="{x}" y="{y}" width="{width}" height="{CELL_SIZE}"><title>Part number {} at row {}, columns {}-{}</title></rect>"#,
+            part_number.value(),
+            part_number.row(),
+            part_number.start_column(),
+            part_number.end_column()
+        );
+    }
+
+    for gear in gears {
+        let (x, y) = corner(gear.coord);
+        let operator = match gear_rule.combine {
+            Combine::Product => " × ",
+            Combine::Sum => " + ",
+        };
+        let values = gear_parts[&gear.coord]
+            .iter()
+            .map(|part_number| part_number.value().to_string())
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            svg,
+            r#"<rect class="gear-outline" x="{x}" y="{y}" width="{CELL_SIZE}" height="{CELL_SIZE}"><title>Gear '{}' at {}: {} = {}</title></rect>"#,
+            escape(gear.character),
+            gear.coord,
+            values.join(operator),
+            gear.value
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Wraps the schematic's [SVG drawing](to_svg) in an HTML page with a summary of its totals.
+pub fn to_html(schematic: &Schematic, gear_rule: &GearRule) -> Result<String, OverflowError> {
+    let part_numbers = schematic.get_part_numbers()?;
+    let gears = Schematic::get_gears(&part_numbers, gear_rule)?;
+
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, r#"<html lang="en">"#);
+    let _ = writeln!(
+        html,
+        "<head>\n<meta charset=\"utf-8\">\n<title>Schematic</title>\n</head>"
+    );
+    let _ = writeln!(html, "<body>");
+    let _ = writeln!(html, "<h1>Schematic</h1>");
+    let _ = writeln!(
+        html,
+        "<p>{} with a sum of {}, and {} with a sum of {}. Hover over a part number or gear to see its value.</p>",
+        plural(part_numbers.len(), "part number"),
+        part_numbers.iter().map(PartNumber::value).sum::<usize>(),
+        plural(gears.len(), "gear"),
+        gears.iter().map(|gear| gear.value).sum::<usize>()
+    );
+    html.push_str(&draw_svg(schematic, gear_rule, &part_numbers, &gears));
+    let _ = writeln!(html, "</body>\n</html>");
+
+    Ok(html)
+}
+
+/// Returns a count followed by a noun, pluralised if the count isn't one.
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        count => format!("{} {}s", count, noun),
+    }
+}
+
+/// The top-left corner of a cell, in pixels.
+fn corner(coord: Coord) -> (usize, usize) {
+    (coord.column * CELL_SIZE, coord.row * CELL_SIZE)
+}
+
+/// The centre of a cell, in pixels.
+fn centre(coord: Coord) -> (usize, usize) {
+    let (x, y) = corner(coord);
+    (x + CELL_SIZE / 2, y + CELL_SIZE / 2)
+}
+
+/// The centre of a part number's digits, in pixels.
+fn span_centre(part_number: &PartNumber) -> (usize, usize) {
+    let (start_x, y) = centre(Coord::new(part_number.row(), part_number.start_column()));
+    let (end_x, _) = centre(Coord::new(part_number.row(), part_number.end_column()));
+    ((start_x + end_x) / 2, y)
+}
+
+/// Escapes a character for use in XML text.
+fn escape(character: char) -> String {
+    match character {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '\'' => "&apos;".to_string(),
+        '"' => "&quot;".to_string(),
+        character => character.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    const TEST_CONTENTS: &str = "12*34\n.....\n5.&..";
+
+    #[test]
+    fn test_to_svg() {
+        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let svg = to_svg(&schematic, &GearRule::default()).unwrap();
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="60""#)
+        );
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<rect class=\"part\"").count(), 4);
+        assert_eq!(svg.matches("<rect class=\"other\"").count(), 1);
+        assert!(svg.contains(r#"<text x="50" y="50">&amp;</text>"#));
+
+        // The gear is joined to the centres of both part numbers
+        assert!(svg.contains(r#"<line x1="50" y1="10" x2="20" y2="10"/>"#));
+        assert!(svg.contains(r#"<line x1="50" y1="10" x2="80" y2="10"/>"#));
+        assert!(svg.contains("<title>Gear '*' at (0, 2): 12 × 34 = 408</title>"));
+        assert!(svg.contains("<title>Part number 34 at row 0, columns 3-4</title>"));
+    }
+
+    #[test]
+    fn test_to_html() {
+        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let html = to_html(&schematic, &GearRule::default()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("2 part numbers with a sum of 46, and 1 gear with a sum of 408"));
+        assert!(html.contains(&to_svg(&schematic, &GearRule::default()).unwrap()));
+
+        let html = to_html(&Schematic::parse_from_contents("1*"), &GearRule::default()).unwrap();
+        assert!(html.contains("1 part number with a sum of 1, and 0 gears with a sum of 0"));
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use grid::Coord;
+
+/// How the values of the part numbers adjacent to a gear are combined.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Combine {
+    #[default]
+    Product,
+    Sum,
+}
+
+impl Combine {
+    /// Combines the values, or returns `None` if the result is too large for a `usize`.
+    pub fn apply(&self, mut values: impl Iterator<Item = usize>) -> Option<usize> {
+        match self {
+            Combine::Product => {
+                values.try_fold(1usize, |product, value| product.checked_mul(value))
+            }
+            Combine::Sum => values.try_fold(0usize, |sum, value| sum.checked_add(value)),
+        }
+    }
+}
+
+impl FromStr for Combine {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "product" => Ok(Combine::Product),
+            "sum" => Ok(Combine::Sum),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Decides which symbols are gears and what their values are.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GearRule {
+    /// The characters that can be gears.
+    pub symbols: BTreeSet<char>,
+    /// How many part numbers must be adjacent to a gear symbol for it to count. Gears are only
+    /// found among symbols adjacent to at least one part number, so a range including 0
+    /// finds the same gears as if it started at 1.
+    pub parts: RangeInclusive<usize>,
+    pub combine: Combine,
+}
+
+impl GearRule {
+    pub fn new(symbols: BTreeSet<char>, parts: RangeInclusive<usize>, combine: Combine) -> Self {
+        Self {
+            symbols,
+            parts,
+            combine,
+        }
+    }
+
+    pub fn is_gear_symbol(&self, character: char) -> bool {
+        self.symbols.contains(&character)
+    }
+}
+
+impl Default for GearRule {
+    /// The puzzle's rule: a '*' adjacent to exactly two part numbers, multiplied together.
+    fn default() -> Self {
+        Self::new(BTreeSet::from(['*']), 2..=2, Combine::Product)
+    }
+}
+
+/// A gear symbol that satisfies a [`GearRule`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Gear {
+    pub coord: Coord,
+    pub character: char,
+    /// The adjacent part numbers' values, combined according to the rule.
+    pub value: usize,
+}
+
+/// Parses a count of parts such as `2`, or an inclusive range such as `2-3`. A count of 0 is
+/// rejected, as a symbol with no adjacent part numbers is never a gear.
+pub fn parse_parts_range(s: &str) -> Option<RangeInclusive<usize>> {
+    let (start, end) = s.split_once('-').unwrap_or((s, s));
+    let (start, end) = (start.parse().ok()?, end.parse().ok()?);
+
+    (0 < start && start <= end).then_some(start..=end)
+}
+
+#[cfg(test)]
+mod gear_tests {
+    use super::*;
+
+    #[test]
+    fn test_combine() {
+        assert_eq!(Combine::Product.apply([2, 3, 4].into_iter()), Some(24));
+        assert_eq!(Combine::Sum.apply([2, 3, 4].into_iter()), Some(9));
+        assert_eq!(Combine::Product.apply([usize::MAX, 2].into_iter()), None);
+        assert_eq!(Combine::Sum.apply([usize::MAX, 1].into_iter()), None);
+        assert_eq!("sum".parse(), Ok(Combine::Sum));
+        assert_eq!("average".parse::<Combine>(), Err(()));
+    }
+
+    #[test]
+    fn test_parse_parts_range() {
+        assert_eq!(parse_parts_range("2"), Some(2..=2));
+        assert_eq!(parse_parts_range("2-3"), Some(2..=3));
+        assert_eq!(parse_parts_range("3-2"), None);
+        assert_eq!(parse_parts_range("0"), None);
+        assert_eq!(parse_parts_range("0-2"), None);
+        assert_eq!(parse_parts_range("two"), None);
+    }
+}
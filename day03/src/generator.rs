@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rng::Rng;
+
+/// Settings for [`generate`].
+pub struct GeneratorConfig {
+    pub width: usize,
+    pub height: usize,
+    /// The chance of a number starting at each free cell.
+    pub number_density: f64,
+    /// The chance of each cell that isn't part of a number holding a symbol.
+    pub symbol_density: f64,
+    /// The chance of each symbol being a `'*'`, which could make it a gear.
+    pub gear_frequency: f64,
+    /// The symbols used when a symbol isn't a `'*'`, which must not be digits, `'.'` or line breaks.
+    pub symbols: Vec<char>,
+    pub seed: u64,
+}
+
+impl Default for GeneratorConfig {
+    /// The size of the puzzle input, with a similar mix of numbers and symbols.
+    fn default() -> Self {
+        Self {
+            width: 140,
+            height: 140,
+            number_density: 0.1,
+            symbol_density: 0.05,
+            gear_frequency: 0.3,
+            symbols: "#$%&+-/=@".chars().collect(),
+            seed: 0,
+        }
+    }
+}
+
+/// A generated schematic, alongside the answers expected for it.
+pub struct GeneratedSchematic {
+    pub contents: String,
+    pub part_number_sum: usize,
+    pub gear_ratio_sum: usize,
+}
+
+/// A character in [`GeneratorConfig::symbols`] that wouldn't be read back as a symbol.
+#[derive(Debug, PartialEq)]
+pub struct InvalidSymbol(pub char);
+
+impl fmt::Display for InvalidSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} can't be a symbol, as it isn't read as one in a schematic",
+            self.0
+        )
+    }
+}
+
+/// A number placed in the schematic, covering `start..=end` of its row.
+struct PlacedNumber {
+    value: usize,
+    row: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Generates a schematic in the puzzle's format.
+///
+/// Numbers have one to three digits, without leading zeros, and are always separated within a row
+/// by at least one other cell, so they may run up to any edge of the grid.
+/// The expected answers are calculated by brute force over the generated cells, independently of
+/// [`crate::schematic::Schematic`], using the puzzle's rules.
+pub fn generate(config: &GeneratorConfig) -> Result<GeneratedSchematic, InvalidSymbol> {
+    if let Some(symbol) = config
+        .symbols
+        .iter()
+        .find(|symbol| matches!(symbol, '.' | '0'..='9' | '\n' | '\r'))
+    {
+        return Err(InvalidSymbol(*symbol));
+    }
+
+    let mut rng = Rng::new(config.seed);
+
+    let mut cells = vec![vec!['.'; config.width]; config.height];
+    let mut numbers = Vec::new();
+
+    for (row, cells) in cells.iter_mut().enumerate() {
+        let mut column = 0;
+        while column < config.width {
+            if rng.chance(config.number_density) {
+                let len = (rng.range(1, 3) as usize).min(config.width - column);
+                let mut value = 0;
+                for (index, cell) in cells[column..column + len].iter_mut().enumerate() {
+                    let digit = rng.range(if index == 0 { 1 } else { 0 }, 9) as usize;
+                    value = value * 10 + digit;
+                    *cell = char::from_digit(digit as u32, 10).unwrap();
+                }
+
+                numbers.push(PlacedNumber {
+                    value,
+                    row,
+                    start: column,
+                    end: column + len - 1,
+                });
+                column += len;
+                if column == config.width {
+                    break;
+                }
+            }
+
+            if rng.chance(config.symbol_density) {
+                cells[column] = if config.symbols.is_empty() || rng.chance(config.gear_frequency) {
+                    '*'
+                } else {
+                    config.symbols[rng.range(0, config.symbols.len() as u64 - 1) as usize]
+                };
+            }
+            column += 1;
+        }
+    }
+
+    let mut part_number_sum = 0;
+    let mut gear_parts: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for number in &numbers {
+        let mut is_part = false;
+
+        let rows = number.row.saturating_sub(1)..=(number.row + 1).min(config.height - 1);
+        let columns = number.start.saturating_sub(1)..=(number.end + 1).min(config.width - 1);
+        for (row, line) in rows.clone().zip(&cells[rows]) {
+            for (column, cell) in columns.clone().zip(&line[columns.clone()]) {
+                if *cell != '.' && !cell.is_ascii_digit() {
+                    is_part = true;
+                    if *cell == '*' {
+                        gear_parts
+                            .entry((row, column))
+                            .or_default()
+                            .push(number.value);
+                    }
+                }
+            }
+        }
+
+        if is_part {
+            part_number_sum += number.value;
+        }
+    }
+
+    let gear_ratio_sum = gear_parts
+        .values()
+        .filter(|parts| parts.len() == 2)
+        .map(|parts| parts[0] * parts[1])
+        .sum();
+
+    Ok(GeneratedSchematic {
+        contents: cells
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        part_number_sum,
+        gear_ratio_sum,
+    })
+}
+
+#[cfg(test)]
+mod generator_tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::gear::GearRule;
+    use crate::schematic::Schematic;
+
+    fn config(width: usize, height: usize, seed: u64) -> GeneratorConfig {
+        GeneratorConfig {
+            width,
+            height,
+            seed,
+            ..GeneratorConfig::default()
+        }
+    }
+
+    /// Solves a generated schematic with [`Schematic`], to compare with the expected answers.
+    fn solve(contents: &str) -> (usize, usize) {
+        let part_numbers = Schematic::parse_from_contents(contents)
+            .get_part_numbers()
+            .unwrap();
+        let gears = Schematic::get_gears(&part_numbers, &GearRule::default()).unwrap();
+
+        (
+            part_numbers
+                .iter()
+                .map(|part_number| part_number.value())
+                .sum(),
+            gears.iter().map(|gear| gear.value).sum(),
+        )
+    }
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let first = generate(&config(50, 50, 7)).unwrap();
+        let second = generate(&config(50, 50, 7)).unwrap();
+        let other = generate(&config(50, 50, 8)).unwrap();
+
+        assert_eq!(first.contents, second.contents);
+        assert_eq!(first.part_number_sum, second.part_number_sum);
+        assert_ne!(first.contents, other.contents);
+    }
+
+    #[test]
+    fn test_generate_shape() {
+        let schematic = generate(&config(60, 40, 1)).unwrap();
+        let lines = schematic.contents.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines.len(), 40);
+        assert!(lines.iter().all(|line| line.chars().count() == 60));
+
+        // Numbers should be placed against every edge of the grid
+        let mut first_column = lines.iter().map(|line| line.chars().next().unwrap());
+        let mut last_column = lines.iter().map(|line| line.chars().last().unwrap());
+        assert!(lines[0].chars().any(|cell| cell.is_ascii_digit()));
+        assert!(lines[39].chars().any(|cell| cell.is_ascii_digit()));
+        assert!(first_column.any(|cell| cell.is_ascii_digit()));
+        assert!(last_column.any(|cell| cell.is_ascii_digit()));
+
+        assert!(schematic.part_number_sum > 0);
+        assert!(schematic.gear_ratio_sum > 0);
+    }
+
+    #[test]
+    fn test_solve_large_generated_schematic() {
+        let schematic = generate(&GeneratorConfig {
+            number_density: 0.2,
+            symbol_density: 0.1,
+            ..config(1000, 1000, 3)
+        })
+        .unwrap();
+
+        assert_eq!(
+            solve(&schematic.contents),
+            (schematic.part_number_sum, schematic.gear_ratio_sum)
+        );
+    }
+
+    #[test]
+    fn test_invalid_symbols() {
+        for symbol in ['.', '7', '\n'] {
+            let config = GeneratorConfig {
+                symbols: vec!['#', symbol],
+                ..GeneratorConfig::default()
+            };
+
+            assert_eq!(generate(&config).err(), Some(InvalidSymbol(symbol)));
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_solve_generated_schematics(
+            width in 1..40usize,
+            height in 1..40usize,
+            number_density in 0.0..1.0f64,
+            symbol_density in 0.0..1.0f64,
+            gear_frequency in 0.0..1.0f64,
+            seed in any::<u64>(),
+        ) {
+            let schematic = generate(&GeneratorConfig {
+                number_density,
+                symbol_density,
+                gear_frequency,
+                ..config(width, height, seed)
+            })
+            .unwrap();
+
+            prop_assert_eq!(
+                solve(&schematic.contents),
+                (schematic.part_number_sum, schematic.gear_ratio_sum)
+            );
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::schematic::{Number, OverflowError, Schematic, Symbol};
+
+/// The pieces of a schematic that don't contribute to any part number.
+pub struct Inventory {
+    /// Numbers that aren't adjacent to any symbol.
+    pub orphan_numbers: Vec<Number>,
+    /// Symbols that aren't adjacent to any number.
+    pub unused_symbols: Vec<Symbol>,
+    /// How many times each symbol's character appears.
+    pub glyph_counts: BTreeMap<char, usize>,
+}
+
+impl Inventory {
+    pub fn new(schematic: &Schematic) -> Result<Self, OverflowError> {
+        let (part_numbers, orphan_numbers): (Vec<_>, Vec<_>) = schematic
+            .get_numbers()?
+            .into_iter()
+            .partition(Number::is_part_number);
+
+        let used_symbols = part_numbers
+            .iter()
+            .flat_map(Number::symbols)
+            .collect::<HashSet<_>>();
+
+        let symbols = schematic.get_symbols();
+        let mut glyph_counts = BTreeMap::new();
+        for symbol in &symbols {
+            *glyph_counts.entry(symbol.character()).or_insert(0) += 1;
+        }
+
+        let unused_symbols = symbols
+            .into_iter()
+            .filter(|symbol| !used_symbols.contains(symbol))
+            .collect();
+
+        Ok(Self {
+            orphan_numbers,
+            unused_symbols,
+            glyph_counts,
+        })
+    }
+}
+
+impl fmt::Display for Inventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Orphan numbers: {}", self.orphan_numbers.len())?;
+        for number in &self.orphan_numbers {
+            writeln!(
+                f,
+                "  {} at row {}, columns {}-{}",
+                number.value(),
+                number.row(),
+                number.start_column(),
+                number.end_column()
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Unused symbols: {}", self.unused_symbols.len())?;
+        for symbol in &self.unused_symbols {
+            writeln!(f, "  '{}' at {}", symbol.character(), symbol.coord())?;
+        }
+
+        writeln!(f)?;
+        writeln!(f, "Symbols by glyph:")?;
+        for (glyph, count) in &self.glyph_counts {
+            writeln!(f, "  '{}': {}", glyph, count)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod inventory_tests {
+    use super::*;
+    use crate::schematic::EXAMPLE;
+    use grid::Coord;
+
+    #[test]
+    fn test_inventory() {
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
+        let inventory = Inventory::new(&schematic).unwrap();
+
+        let orphans = inventory
+            .orphan_numbers
+            .iter()
+            .map(|number| (number.value(), number.row(), number.start_column()))
+            .collect::<Vec<_>>();
+        assert_eq!(orphans, vec![(114, 0, 5), (58, 5, 7)]);
+
+        assert!(inventory.unused_symbols.is_empty());
+        assert_eq!(
+            inventory.glyph_counts,
+            BTreeMap::from([('#', 1), ('$', 1), ('*', 3), ('+', 1)])
+        );
+    }
+
+    #[test]
+    fn test_unused_symbols() {
+        let schematic = Schematic::parse_from_contents("1.#\n@.*\n...");
+        let inventory = Inventory::new(&schematic).unwrap();
+
+        let unused = inventory
+            .unused_symbols
+            .iter()
+            .map(|symbol| (symbol.character(), symbol.coord()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unused,
+            vec![('#', Coord::new(0, 2)), ('*', Coord::new(1, 2))]
+        );
+        assert!(inventory.orphan_numbers.is_empty());
+        assert_eq!(
+            inventory.to_string(),
+            "Orphan numbers: 0\n\nUnused symbols: 2\n  '#' at (0, 2)\n  '*' at (1, 2)\n\nSymbols by glyph:\n  '#': 1\n  '*': 1\n  '@': 1\n"
+        );
+    }
+}
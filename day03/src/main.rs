@@ -1,22 +1,696 @@
+mod classification;
+mod components;
+mod editor;
+mod export;
+mod gear;
+mod generator;
+mod inventory;
+mod query;
+mod render;
 mod schematic;
+mod stream;
+mod validation;
 
-use schematic::Schematic;
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Instant;
+
+use classification::{CharacterSet, Classification};
+use editor::IncrementalSchematic;
+use gear::{Combine, GearRule};
+use generator::GeneratorConfig;
+use grid::{Adjacency, Coord, Neighbourhood, MAX_RADIUS};
+use query::SchematicQuery;
+use render::RenderOptions;
+use schematic::{Number, OverflowError, Schematic, Symbol};
+use stream::{Event, SchematicStream};
+use validation::{RaggedRows, SchematicValidator};
 
 fn main() {
-    let puzzle_input = include_str!("../input.txt");
+    let mut args = std::env::args().skip(1).collect::<Vec<_>>();
+    let options = take_schematic_options(&mut args);
+
+    let Some((command, args)) = args.split_first() else {
+        solve(&options.load(None));
+        return;
+    };
+
+    match command.as_str() {
+        "parts" => list_part_numbers(path_arg(args, "parts [path]"), &options),
+        "gears" => list_gears(args, &options),
+        "bench" => bench(path_arg(args, "bench [size]"), &options),
+        "render" => render(args, &options),
+        "export" => {
+            let args = positional_args(args, 2, "export <svg|html> [path]");
+            export(args.first(), args.get(1), &options)
+        }
+        "inventory" => print_inventory(path_arg(args, "inventory [path]"), &options),
+        "components" => print_components(path_arg(args, "components [path]"), &options),
+        "stream" => solve_streaming(path_arg(args, "stream [path]"), &options),
+        "edit" => edit(args, &options),
+        "query" => query(path_arg(args, "query [path]"), &options),
+        "generate" => generate_schematic(args),
+        _ => {
+            eprintln!(
+                "Unknown command '{}', expected one of parts, gears, bench, render, export, inventory, components, stream, edit, query or generate",
+                command
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Returns a subcommand's arguments, exiting with its usage if there are more than `max` of
+/// them or any of them looks like an option, as every option it takes has already been removed.
+fn positional_args<'a>(args: &'a [String], max: usize, usage: &str) -> &'a [String] {
+    if args.len() > max || args.iter().any(|arg| arg.starts_with("--")) {
+        eprintln!("Usage: {}", usage);
+        std::process::exit(1);
+    }
+    args
+}
+
+/// Returns the only argument of a subcommand that takes at most one, such as a path.
+fn path_arg<'a>(args: &'a [String], usage: &str) -> Option<&'a String> {
+    positional_args(args, 1, usage).first()
+}
+
+/// Options that change how every command reads a schematic.
+struct SchematicOptions {
+    neighbourhood: Neighbourhood,
+    classification: Classification,
+    /// Checks schematics before they are parsed, if strict validation is enabled.
+    validator: Option<SchematicValidator>,
+}
+
+impl SchematicOptions {
+    /// Parses a schematic, exiting with each problem found if it fails validation.
+    fn parse(&self, contents: &str) -> Schematic {
+        let schematic = match &self.validator {
+            Some(validator) => validator.parse(contents).unwrap_or_else(|errors| {
+                eprintln!("Invalid schematic:");
+                for error in errors {
+                    eprintln!("  {}", error);
+                }
+                std::process::exit(1);
+            }),
+            None => Schematic::parse_from_contents(contents),
+        };
+
+        schematic
+            .with_neighbourhood(self.neighbourhood)
+            .with_classification(self.classification.clone())
+    }
 
-    let schematic = Schematic::parse_from_contents(puzzle_input);
+    /// Reads and parses the schematic at a path, or the puzzle input if there is no path.
+    fn load(&self, path: Option<&String>) -> Schematic {
+        self.parse(&read_file_or_input(path))
+    }
+}
+
+/// Removes `--neighbourhood <von-neumann|moore|moore:radius>`, `--wrap`, `--classification <path>`,
+/// `--strict <reject|pad>` and `--alphabet <characters>` from the arguments, returning the
+/// [`SchematicOptions`] they configure.
+fn take_schematic_options(args: &mut Vec<String>) -> SchematicOptions {
+    let mut neighbourhood = Neighbourhood::default();
+
+    if let Some(index) = args.iter().position(|arg| arg == "--neighbourhood") {
+        neighbourhood.adjacency = args
+            .get(index + 1)
+            .and_then(|adjacency| adjacency.parse::<Adjacency>().ok())
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "Usage: --neighbourhood <von-neumann|moore|moore:radius>, with a radius up to {}",
+                    MAX_RADIUS
+                );
+                std::process::exit(1);
+            });
+        args.drain(index..index + 2);
+    }
+
+    if let Some(index) = args.iter().position(|arg| arg == "--wrap") {
+        neighbourhood.wrap = true;
+        args.remove(index);
+    }
+
+    let mut classification = Classification::default();
+    if let Some(index) = args.iter().position(|arg| arg == "--classification") {
+        let Some(path) = args.get(index + 1) else {
+            eprintln!("Usage: --classification <path>");
+            std::process::exit(1);
+        };
+
+        classification = Classification::parse(&read_file(path)).unwrap_or_else(|err| {
+            eprintln!("Failed to load {}: {}", path, err);
+            std::process::exit(1);
+        });
+        args.drain(index..index + 2);
+    }
+
+    let mut validator = None;
+    if let Some(index) = args.iter().position(|arg| arg == "--strict") {
+        let ragged_rows = args
+            .get(index + 1)
+            .and_then(|ragged_rows| ragged_rows.parse::<RaggedRows>().ok())
+            .unwrap_or_else(|| {
+                eprintln!("Usage: --strict <reject|pad>");
+                std::process::exit(1);
+            });
+        args.drain(index..index + 2);
+
+        validator = Some(SchematicValidator {
+            ragged_rows,
+            ..Default::default()
+        });
+    }
+
+    if let Some(index) = args.iter().position(|arg| arg == "--alphabet") {
+        let alphabet = args
+            .get(index + 1)
+            .and_then(|alphabet| CharacterSet::parse(alphabet).ok())
+            .unwrap_or_else(|| {
+                eprintln!("Usage: --alphabet <characters>");
+                std::process::exit(1);
+            });
+        args.drain(index..index + 2);
+
+        validator
+            .get_or_insert_with(SchematicValidator::default)
+            .alphabet = alphabet;
+    }
+
+    SchematicOptions {
+        neighbourhood,
+        classification,
+        validator,
+    }
+}
 
+fn solve(schematic: &Schematic) {
     // Part 1
-    let part_numbers = schematic.get_part_numbers();
+    let part_numbers = exit_on_overflow(schematic.get_part_numbers());
     let part_number_sum = part_numbers
         .iter()
-        .map(|part_number| part_number.0)
+        .map(|part_number| part_number.value())
         .sum::<usize>();
     println!("Part number sum: {}", part_number_sum);
 
     // Part 2
-    let gear_ratios = Schematic::get_gear_ratios(&part_numbers);
-    let gear_ratio_sum = gear_ratios.iter().sum::<usize>();
+    let gears = exit_on_overflow(Schematic::get_gears(&part_numbers, &GearRule::default()));
+    let gear_ratio_sum = gears.iter().map(|gear| gear.value).sum::<usize>();
     println!("Gear ratio sum: {}", gear_ratio_sum);
 }
+
+/// Solves the puzzle while reading the schematic a row at a time, rather than all at once.
+fn solve_streaming(path: Option<&String>, options: &SchematicOptions) {
+    let stream = SchematicStream::new(
+        options.classification.clone(),
+        options.neighbourhood,
+        GearRule::default(),
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    let stream = match &options.validator {
+        Some(validator) => stream.with_validator(validator.clone()),
+        None => stream,
+    };
+
+    let mut part_number_sum = 0;
+    let mut gear_ratio_sum = 0;
+    let on_event = |event| match event {
+        Event::PartNumber(part_number) => part_number_sum += part_number.value(),
+        Event::Gear(gear) => gear_ratio_sum += gear.value,
+    };
+
+    let result = match path {
+        Some(path) => {
+            let file = File::open(path).unwrap_or_else(|err| {
+                eprintln!("Failed to read {}: {}", path, err);
+                std::process::exit(1);
+            });
+            stream.process(BufReader::new(file), on_event)
+        }
+        None => stream.process(include_str!("../input.txt").as_bytes(), on_event),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+
+    println!("Part number sum: {}", part_number_sum);
+    println!("Gear ratio sum: {}", gear_ratio_sum);
+}
+
+/// Applies edits read from stdin to the schematic, printing the updated answers after each one.
+/// Each line is one of `set <row> <column> <character>`, `insert <row> [cells]` or `delete <row>`.
+/// Gears follow the rule given by the same options as `gears`, and edits are checked by the
+/// same validation as the schematic.
+fn edit(args: &[String], options: &SchematicOptions) {
+    let (gear_rule, path) = parse_gear_rule_args(args, print_edit_usage);
+    let mut schematic = IncrementalSchematic::new(options.load(path), gear_rule);
+
+    for line in std::io::stdin().lines() {
+        let line = line.unwrap_or_else(|err| {
+            eprintln!("Failed to read an edit: {}", err);
+            std::process::exit(1);
+        });
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+
+        let grid = schematic.schematic().grid();
+        let (height, width) = (grid.height(), grid.width());
+        let index = |i: usize, len: usize| {
+            parts
+                .get(i)
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|index| *index < len)
+        };
+
+        match parts.as_slice() {
+            ["set", _, _, character] if character.chars().count() == 1 => {
+                match (index(1, height), index(2, width)) {
+                    (Some(row), Some(column)) => {
+                        let character = character.chars().next().unwrap();
+                        let error = options.validator.as_ref().and_then(|validator| {
+                            validator.validate_character(row + 1, column + 1, character)
+                        });
+                        if let Some(error) = error {
+                            eprintln!("Invalid edit: {}", error);
+                            continue;
+                        }
+
+                        schematic.set_cell(Coord::new(row, column), character);
+                    }
+                    _ => {
+                        eprintln!("Cell is outside the {}x{} schematic", height, width);
+                        continue;
+                    }
+                }
+            }
+            ["insert", _] | ["insert", _, _]
+                if parts.get(2).map_or(0, |cells| cells.chars().count()) <= width =>
+            {
+                let Some(row) = index(1, height + 1) else {
+                    eprintln!("Row is outside the schematic");
+                    continue;
+                };
+                let cells = parts.get(2).unwrap_or(&"");
+
+                if let Some(validator) = &options.validator {
+                    let errors = validator.validate_row(row + 1, cells, width);
+                    if !errors.is_empty() {
+                        for error in errors {
+                            eprintln!("Invalid edit: {}", error);
+                        }
+                        continue;
+                    }
+                }
+                schematic.insert_row(row, cells);
+            }
+            ["delete", _] => {
+                let Some(row) = index(1, height) else {
+                    eprintln!("Row is outside the schematic");
+                    continue;
+                };
+                schematic.remove_row(row);
+            }
+            _ => {
+                eprintln!(
+                    "Usage: set <row> <column> <character> | insert <row> [cells] | delete <row>"
+                );
+                continue;
+            }
+        }
+
+        // A number can be too large part way through a series of edits, so this isn't fatal
+        match (schematic.part_numbers(), schematic.gears()) {
+            (Ok(part_numbers), Ok(gears)) => println!(
+                "Part number sum: {}, gear ratio sum: {}",
+                part_numbers
+                    .iter()
+                    .map(|part_number| part_number.value())
+                    .sum::<usize>(),
+                gears.iter().map(|gear| gear.value).sum::<usize>()
+            ),
+            (Err(err), _) | (_, Err(err)) => eprintln!("Can't solve the schematic: {}", err),
+        }
+    }
+}
+
+/// Answers questions about cells of the schematic read from stdin.
+/// Each line is one of `at <row> <column>`, `symbols <row> <column>`, `parts <row> <column>`
+/// or `region <row> <column> <row> <column>`.
+fn query(path: Option<&String>, options: &SchematicOptions) {
+    let query = exit_on_overflow(SchematicQuery::new(&options.load(path)));
+
+    for line in std::io::stdin().lines() {
+        let line = line.unwrap_or_else(|err| {
+            eprintln!("Failed to read a query: {}", err);
+            std::process::exit(1);
+        });
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+        // Indexes are read in pairs, so an odd number of them is rejected
+        let coords = parts
+            .iter()
+            .skip(1)
+            .map(|index| index.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|indexes| indexes.len() % 2 == 0)
+            .unwrap_or_default()
+            .chunks(2)
+            .map(|pair| Coord::new(pair[0], pair[1]))
+            .collect::<Vec<_>>();
+
+        match (parts.first().copied(), coords.as_slice()) {
+            (Some("at"), [coord]) => {
+                match (query.number_at(*coord), query.symbol_at(*coord)) {
+                    (Some(number), _) => println!("{}", describe_number(number)),
+                    (None, Some(symbol)) => println!("{}", describe_symbol(&symbol)),
+                    (None, None) => println!("Nothing at {}", coord),
+                }
+            }
+            (Some("symbols"), [coord]) => {
+                for symbol in query.symbols_adjacent_to_number(*coord) {
+                    println!("{}", describe_symbol(symbol));
+                }
+            }
+            (Some("parts"), [coord]) => {
+                for number in query.numbers_adjacent_to_symbol(*coord) {
+                    println!("{}", describe_number(number));
+                }
+            }
+            (Some("region"), [top_left, bottom_right]) => {
+                let region = query.region(*top_left, *bottom_right);
+                for number in region.numbers {
+                    println!("{}", describe_number(number));
+                }
+                for symbol in region.symbols {
+                    println!("{}", describe_symbol(&symbol));
+                }
+            }
+            _ => eprintln!(
+                "Usage: at <row> <column> | symbols <row> <column> | parts <row> <column> | region <row> <column> <row> <column>"
+            ),
+        }
+    }
+}
+
+fn describe_number(number: &Number) -> String {
+    format!(
+        "{} at row {}, columns {}-{}",
+        number.value(),
+        number.row(),
+        number.start_column(),
+        number.end_column()
+    )
+}
+
+fn describe_symbol(symbol: &Symbol) -> String {
+    format!("'{}' at {}", symbol.character(), symbol.coord())
+}
+
+/// Prints a generated schematic, and writes its expected answers to stderr.
+/// Arguments: `<seed> [width] [height] [number-density] [symbol-density] [gear-frequency] [symbols]`
+fn generate_schematic(args: &[String]) {
+    let defaults = GeneratorConfig::default();
+    let size = |index: usize, default: usize| {
+        args.get(index)
+            .map_or(Some(default), |arg| arg.parse::<usize>().ok())
+            .filter(|size| *size > 0)
+    };
+    let probability = |index: usize, default: f64| {
+        args.get(index)
+            .map_or(Some(default), |arg| arg.parse::<f64>().ok())
+            .filter(|probability| (0.0..=1.0).contains(probability))
+    };
+    let symbols = args
+        .get(6)
+        .map_or(defaults.symbols.clone(), |arg| arg.chars().collect());
+
+    let config = (|| {
+        Some(GeneratorConfig {
+            seed: args.first()?.parse().ok()?,
+            width: size(1, defaults.width)?,
+            height: size(2, defaults.height)?,
+            number_density: probability(3, defaults.number_density)?,
+            symbol_density: probability(4, defaults.symbol_density)?,
+            gear_frequency: probability(5, defaults.gear_frequency)?,
+            symbols,
+        })
+    })()
+    .filter(|_| args.len() <= 7);
+
+    let Some(config) = config else {
+        eprintln!(
+            "Usage: day03 generate <seed> [width] [height] [number-density] [symbol-density] [gear-frequency] [symbols]"
+        );
+        std::process::exit(1);
+    };
+
+    let schematic = generator::generate(&config).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
+    println!("{}", schematic.contents);
+    eprintln!("Part number sum: {}", schematic.part_number_sum);
+    eprintln!("Gear ratio sum: {}", schematic.gear_ratio_sum);
+}
+
+/// Prints each part number with its position and the symbols it is adjacent to.
+fn list_part_numbers(path: Option<&String>, options: &SchematicOptions) {
+    let schematic = options.load(path);
+
+    for part_number in exit_on_overflow(schematic.get_part_numbers()) {
+        let symbols = part_number
+            .symbols()
+            .iter()
+            .map(describe_symbol)
+            .collect::<Vec<_>>();
+
+        println!(
+            "{} at row {}, columns {}-{}: next to {}",
+            part_number.value(),
+            part_number.row(),
+            part_number.start_column(),
+            part_number.end_column(),
+            symbols.join(", ")
+        );
+    }
+}
+
+fn print_edit_usage() -> ! {
+    eprintln!(
+        "Usage: edit [--symbols <characters>] [--parts <count|min-max>] [--combine <product|sum>] [path]"
+    );
+    std::process::exit(1);
+}
+
+/// Prints each gear matching a rule given by the options, and the sum of their values.
+fn list_gears(args: &[String], options: &SchematicOptions) {
+    let (rule, path) = parse_gear_rule_args(args, print_gears_usage);
+    let schematic = options.load(path);
+    let part_numbers = exit_on_overflow(schematic.get_part_numbers());
+    let gears = exit_on_overflow(Schematic::get_gears(&part_numbers, &rule));
+
+    for gear in &gears {
+        println!("'{}' at {}: {}", gear.character, gear.coord, gear.value);
+    }
+    println!(
+        "Sum of gear values: {}",
+        gears.iter().map(|gear| gear.value).sum::<usize>()
+    );
+}
+
+/// Parses `[--symbols <characters>] [--parts <count|min-max>] [--combine <product|sum>] [path]`
+/// into a gear rule, starting from the puzzle's rule, calling `usage` if they are invalid.
+fn parse_gear_rule_args(args: &[String], usage: fn() -> !) -> (GearRule, Option<&String>) {
+    let mut rule = GearRule::default();
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--symbols" => {
+                rule.symbols = args
+                    .next()
+                    .map(|symbols| symbols.chars().collect())
+                    .unwrap_or_default();
+            }
+            "--parts" => {
+                rule.parts = args
+                    .next()
+                    .and_then(|parts| gear::parse_parts_range(parts))
+                    .unwrap_or_else(|| usage());
+            }
+            "--combine" => {
+                rule.combine = args
+                    .next()
+                    .and_then(|combine| combine.parse::<Combine>().ok())
+                    .unwrap_or_else(|| usage());
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => usage(),
+        }
+    }
+
+    if rule.symbols.is_empty() {
+        usage();
+    }
+
+    (rule, path)
+}
+
+fn print_gears_usage() -> ! {
+    eprintln!(
+        "Usage: gears [--symbols <characters>] [--parts <count|min-max>] [--combine <product|sum>] [path]"
+    );
+    std::process::exit(1);
+}
+
+/// Prints the schematic with its part numbers, symbols and gears coloured.
+fn render(args: &[String], options: &SchematicOptions) {
+    let mut render_options = RenderOptions::default();
+    let mut path = None;
+
+    let mut args = args.iter().peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rulers" => render_options.rulers = true,
+            "--around" => {
+                let mut number = || {
+                    args.next()
+                        .and_then(|number| number.parse::<usize>().ok())
+                        .unwrap_or_else(|| print_render_usage())
+                };
+                let centre = Coord::new(number(), number());
+
+                // The radius is optional, so only take the next argument if it is a number
+                let radius = args
+                    .next_if(|radius| radius.parse::<usize>().is_ok())
+                    .map_or(10, |radius| radius.parse().unwrap());
+                render_options.viewport = Some((centre, radius));
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => print_render_usage(),
+        }
+    }
+
+    let schematic = options.load(path);
+    print!(
+        "{}",
+        exit_on_overflow(render::render(&schematic, &render_options))
+    );
+}
+
+fn print_render_usage() -> ! {
+    eprintln!("Usage: render [--rulers] [--around <row> <column> [radius]] [path]");
+    std::process::exit(1);
+}
+
+/// Prints the schematic as an SVG image or HTML page.
+fn export(format: Option<&String>, path: Option<&String>, options: &SchematicOptions) {
+    let schematic = options.load(path);
+    let gear_rule = GearRule::default();
+
+    match format.map(String::as_str) {
+        Some("svg") => print!(
+            "{}",
+            exit_on_overflow(export::to_svg(&schematic, &gear_rule))
+        ),
+        Some("html") => print!(
+            "{}",
+            exit_on_overflow(export::to_html(&schematic, &gear_rule))
+        ),
+        _ => {
+            eprintln!("Usage: export <svg|html> [path]");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints the numbers and symbols that don't contribute to any part number, and counts of each symbol.
+fn print_inventory(path: Option<&String>, options: &SchematicOptions) {
+    let schematic = options.load(path);
+    print!(
+        "{}",
+        exit_on_overflow(inventory::Inventory::new(&schematic))
+    );
+}
+
+/// Prints each group of numbers and symbols that touch each other.
+fn print_components(path: Option<&String>, options: &SchematicOptions) {
+    let schematic = options.load(path);
+
+    let components = exit_on_overflow(components::find_components(&schematic));
+    for (index, component) in components.iter().enumerate() {
+        println!("Component {}: {}", index + 1, component);
+    }
+}
+
+/// Times each step of solving a square schematic made by tiling the puzzle's example.
+fn bench(size: Option<&String>, options: &SchematicOptions) {
+    let size = match size {
+        Some(size) => size.parse::<usize>().unwrap_or_else(|_| {
+            eprintln!("Usage: bench [size]");
+            std::process::exit(1);
+        }),
+        None => 10_000,
+    };
+
+    let tile = schematic::EXAMPLE.lines().collect::<Vec<_>>();
+    let contents = (0..size)
+        .map(|row| {
+            tile[row % tile.len()]
+                .chars()
+                .cycle()
+                .take(size)
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let start = Instant::now();
+    let schematic = options.parse(&contents);
+    println!(
+        "Parsed a {}x{} schematic in {:?}",
+        size,
+        size,
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let part_numbers = exit_on_overflow(schematic.get_part_numbers());
+    println!(
+        "Found {} part numbers in {:?}",
+        part_numbers.len(),
+        start.elapsed()
+    );
+
+    let start = Instant::now();
+    let gears = exit_on_overflow(Schematic::get_gears(&part_numbers, &GearRule::default()));
+    println!("Found {} gears in {:?}", gears.len(), start.elapsed());
+}
+
+/// Returns the result, or exits with the error if a value in the schematic is too large.
+fn exit_on_overflow<T>(result: Result<T, OverflowError>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    })
+}
+
+fn read_file_or_input(path: Option<&String>) -> String {
+    match path {
+        Some(path) => read_file(path),
+        None => include_str!("../input.txt").to_string(),
+    }
+}
+
+fn read_file(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Failed to read {}: {}", path, err);
+        std::process::exit(1);
+    })
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+
+use grid::{Coord, Grid};
+
+use crate::schematic::{Number, OverflowError, Schematic, Symbol};
+
+/// Everything within a rectangular region of a schematic.
+#[derive(Debug, Default, PartialEq)]
+pub struct Region<'a> {
+    /// The numbers with at least one digit in the region.
+    pub numbers: Vec<&'a Number>,
+    pub symbols: Vec<Symbol>,
+}
+
+/// Answers questions about specific cells of a schematic, after scanning it once.
+pub struct SchematicQuery {
+    /// Every number in the schematic, including those with no adjacent symbols.
+    numbers: Vec<Number>,
+    /// The index of the number covering each cell, if any.
+    number_cells: Grid<Option<usize>>,
+    symbols: Grid<Option<Symbol>>,
+    /// The indexes of the numbers adjacent to each symbol.
+    symbol_numbers: HashMap<Coord, Vec<usize>>,
+}
+
+impl SchematicQuery {
+    pub fn new(schematic: &Schematic) -> Result<Self, OverflowError> {
+        let grid = schematic.grid();
+        let numbers = schematic.get_numbers()?;
+
+        let mut number_cells = Grid::new(
+            grid.width(),
+            grid.height(),
+            vec![None; grid.width() * grid.height()],
+        );
+        let mut symbol_numbers: HashMap<Coord, Vec<usize>> = HashMap::new();
+        for (index, number) in numbers.iter().enumerate() {
+            for column in number.start_column()..=number.end_column() {
+                number_cells[Coord::new(number.row(), column)] = Some(index);
+            }
+            for symbol in number.symbols() {
+                symbol_numbers
+                    .entry(symbol.coord())
+                    .or_default()
+                    .push(index);
+            }
+        }
+
+        let mut symbols = Grid::new(
+            grid.width(),
+            grid.height(),
+            vec![None; grid.width() * grid.height()],
+        );
+        for symbol in schematic.get_symbols() {
+            symbols[symbol.coord()] = Some(symbol);
+        }
+
+        Ok(Self {
+            numbers,
+            number_cells,
+            symbols,
+            symbol_numbers,
+        })
+    }
+
+    /// Returns the number with a digit in the cell, which is a part number if it has any symbols.
+    pub fn number_at(&self, coord: Coord) -> Option<&Number> {
+        let index = (*self.number_cells.get(coord)?)?;
+        Some(&self.numbers[index])
+    }
+
+    /// Returns the symbol in the cell, if there is one.
+    pub fn symbol_at(&self, coord: Coord) -> Option<Symbol> {
+        *self.symbols.get(coord)?
+    }
+
+    /// Returns the symbols adjacent to the number with a digit in the cell.
+    pub fn symbols_adjacent_to_number(&self, coord: Coord) -> &[Symbol] {
+        self.number_at(coord).map_or(&[], Number::symbols)
+    }
+
+    /// Returns the part numbers adjacent to the symbol in the cell.
+    pub fn numbers_adjacent_to_symbol(&self, coord: Coord) -> Vec<&Number> {
+        self.symbol_numbers
+            .get(&coord)
+            .map(|indexes| indexes.iter().map(|index| &self.numbers[*index]).collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the numbers and symbols within the rectangle between two corners (inclusive).
+    pub fn region(&self, top_left: Coord, bottom_right: Coord) -> Region<'_> {
+        let mut region = Region::default();
+        let mut last_number = None;
+
+        for (coord, number) in self.number_cells.region(top_left, bottom_right) {
+            // Each number covers consecutive cells, so only the first digit in the region is added
+            if let Some(index) = number {
+                if last_number != Some((coord.row, *index)) {
+                    region.numbers.push(&self.numbers[*index]);
+                    last_number = Some((coord.row, *index));
+                }
+            }
+            if let Some(symbol) = self.symbols[coord] {
+                region.symbols.push(symbol);
+            }
+        }
+
+        region
+    }
+}
+
+#[cfg(test)]
+mod query_tests {
+    use super::*;
+    use crate::schematic::EXAMPLE;
+
+    fn query() -> SchematicQuery {
+        SchematicQuery::new(&Schematic::parse_from_contents(EXAMPLE)).unwrap()
+    }
+
+    #[test]
+    fn test_number_at() {
+        let query = query();
+
+        assert_eq!(
+            query.number_at(Coord::new(0, 1)).map(Number::value),
+            Some(467)
+        );
+        assert_eq!(
+            query.number_at(Coord::new(0, 7)).map(Number::value),
+            Some(114)
+        );
+        assert_eq!(query.number_at(Coord::new(0, 3)), None);
+        assert_eq!(query.number_at(Coord::new(20, 0)), None);
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let query = query();
+
+        assert_eq!(
+            query.symbols_adjacent_to_number(Coord::new(2, 3)),
+            &[Symbol::new('*', Coord::new(1, 3))]
+        );
+        assert!(query
+            .symbols_adjacent_to_number(Coord::new(0, 5))
+            .is_empty());
+        assert!(query
+            .symbols_adjacent_to_number(Coord::new(1, 3))
+            .is_empty());
+
+        let values = query
+            .numbers_adjacent_to_symbol(Coord::new(8, 5))
+            .iter()
+            .map(|number| number.value())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![755, 598]);
+        assert!(query
+            .numbers_adjacent_to_symbol(Coord::new(0, 0))
+            .is_empty());
+        assert_eq!(
+            query.symbol_at(Coord::new(5, 5)),
+            Some(Symbol::new('+', Coord::new(5, 5)))
+        );
+    }
+
+    #[test]
+    fn test_region() {
+        let query = query();
+        let region = query.region(Coord::new(0, 2), Coord::new(2, 3));
+
+        let values = region
+            .numbers
+            .iter()
+            .map(|number| number.value())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![467, 35]);
+        assert_eq!(region.symbols, vec![Symbol::new('*', Coord::new(1, 3))]);
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt::Write;
+
+use grid::Coord;
+
+use crate::gear::GearRule;
+use crate::schematic::{CellKind, OverflowError, Schematic};
+
+const RESET: &str = "\x1b[0m";
+
+/// Returns the ANSI escape code that starts the style for a kind of cell.
+fn style_code(kind: CellKind) -> &'static str {
+    match kind {
+        CellKind::Blank => "\x1b[2m",
+        CellKind::PartNumber => "\x1b[1;32m",
+        CellKind::OtherNumber => "\x1b[31m",
+        CellKind::Symbol => "\x1b[33m",
+        CellKind::Gear => "\x1b[1;30;45m",
+    }
+}
+
+/// Options for rendering a schematic to a terminal.
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Whether to label the rows and columns.
+    pub rulers: bool,
+    /// Only draw the cells within this many rows and columns of a coordinate.
+    pub viewport: Option<(Coord, usize)>,
+    pub gear_rule: GearRule,
+}
+
+/// Draws the schematic with ANSI colours: part numbers in green, other numbers in red,
+/// symbols in yellow and gears highlighted in magenta.
+pub fn render(schematic: &Schematic, options: &RenderOptions) -> Result<String, OverflowError> {
+    let grid = schematic.grid();
+    let part_numbers = schematic.get_part_numbers()?;
+    let gears = Schematic::get_gears(&part_numbers, &options.gear_rule)?;
+    let kinds = schematic.classify(&part_numbers, &gears);
+
+    let (top_left, bottom_right) = match options.viewport {
+        Some((centre, radius)) => (
+            Coord::new(
+                centre.row.saturating_sub(radius),
+                centre.column.saturating_sub(radius),
+            ),
+            Coord::new(
+                centre.row.saturating_add(radius),
+                centre.column.saturating_add(radius),
+            ),
+        ),
+        None => (Coord::default(), Coord::new(usize::MAX, usize::MAX)),
+    };
+    let last_row = bottom_right.row.min(grid.height().saturating_sub(1));
+    let last_column = bottom_right.column.min(grid.width().saturating_sub(1));
+
+    let margin = last_row.to_string().len();
+    let mut output = String::new();
+
+    if options.rulers && top_left.column <= last_column {
+        // Label every tenth column above a line of each column's last digit
+        let mut labels = String::new();
+        for column in top_left.column..=last_column {
+            if column % 10 == 0 {
+                let _ = write!(labels, "{:<10}", column);
+            } else if column == top_left.column {
+                labels.push_str(&" ".repeat(10 - column % 10));
+            }
+        }
+        let width = last_column - top_left.column + 1;
+        let labels = labels.chars().take(width).collect::<String>();
+        let digits = (top_left.column..=last_column)
+            .map(|column| char::from_digit((column % 10) as u32, 10).unwrap())
+            .collect::<String>();
+
+        if !labels.trim().is_empty() {
+            let _ = writeln!(output, "{:margin$} {}", "", labels.trim_end());
+        }
+        let _ = writeln!(output, "{:margin$} {}", "", digits);
+    }
+
+    let mut row_start = None;
+    let mut current_style = None;
+    for (coord, character) in grid.region(top_left, bottom_right) {
+        if row_start != Some(coord.row) {
+            if row_start.is_some() {
+                output.push_str(RESET);
+                output.push('\n');
+                current_style = None;
+            }
+            if options.rulers {
+                let _ = write!(output, "{:>margin$} ", coord.row);
+            }
+            row_start = Some(coord.row);
+        }
+
+        let kind = kinds[coord];
+        if current_style != Some(kind) {
+            output.push_str(RESET);
+            output.push_str(style_code(kind));
+            current_style = Some(kind);
+        }
+        output.push(*character);
+    }
+    if row_start.is_some() {
+        output.push_str(RESET);
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    /// Removes the ANSI escape codes from rendered output.
+    fn strip_codes(output: &str) -> String {
+        let mut stripped = String::new();
+        let mut in_code = false;
+        for character in output.chars() {
+            match character {
+                '\x1b' => in_code = true,
+                'm' if in_code => in_code = false,
+                _ if in_code => {}
+                _ => stripped.push(character),
+            }
+        }
+        stripped
+    }
+
+    const TEST_CONTENTS: &str = "12*34\n.....\n5.#..";
+
+    #[test]
+    fn test_render_styles() {
+        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let output = render(&schematic, &RenderOptions::default()).unwrap();
+
+        assert_eq!(strip_codes(&output), format!("{}\n", TEST_CONTENTS));
+
+        let lines = output.lines().collect::<Vec<_>>();
+        let part_number = style_code(CellKind::PartNumber);
+        assert_eq!(
+            lines[0],
+            format!(
+                "{RESET}{part_number}12{RESET}{}*{RESET}{part_number}34{RESET}",
+                style_code(CellKind::Gear)
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "{RESET}{}5{RESET}{}.{RESET}{}#{RESET}{}..{RESET}",
+                style_code(CellKind::OtherNumber),
+                style_code(CellKind::Blank),
+                style_code(CellKind::Symbol),
+                style_code(CellKind::Blank)
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_rulers() {
+        let contents = ["0123456789012", "............."].join("\n");
+        let schematic = Schematic::parse_from_contents(&contents);
+        let options = RenderOptions {
+            rulers: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            strip_codes(&render(&schematic, &options).unwrap()),
+            "  0         10\n  0123456789012\n0 0123456789012\n1 .............\n"
+        );
+    }
+
+    #[test]
+    fn test_render_viewport() {
+        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let options = RenderOptions {
+            rulers: true,
+            viewport: Some((Coord::new(2, 3), 1)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            strip_codes(&render(&schematic, &options).unwrap()),
+            "  234\n1 ...\n2 #..\n"
+        );
+    }
+}
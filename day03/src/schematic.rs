@@ -1,280 +1,791 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+use grid::{Coord, Grid, Neighbourhood};
+
+use crate::classification::{CellClass, Classification};
+use crate::gear::{Gear, GearRule};
+
+/// A character in the schematic that its [`Classification`] counts as a symbol.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Symbol {
+    coord: Coord,
     character: char,
-    row_index: usize,
-    column_index: usize,
+}
+
+impl Symbol {
+    pub fn new(character: char, coord: Coord) -> Self {
+        Self { coord, character }
+    }
+
+    pub fn character(&self) -> char {
+        self.character
+    }
+
+    pub fn coord(&self) -> Coord {
+        self.coord
+    }
+}
+
+/// A number in the schematic, alongside the symbols it is adjacent to, if any.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Number {
+    value: usize,
+    row: usize,
+    start_column: usize,
+    end_column: usize,
+    symbols: Vec<Symbol>,
+}
+
+impl Number {
+    /// Creates a number spanning `start_column..=end_column` of a row.
+    pub fn new(
+        value: usize,
+        row: usize,
+        start_column: usize,
+        end_column: usize,
+        symbols: Vec<Symbol>,
+    ) -> Self {
+        Self {
+            value,
+            row,
+            start_column,
+            end_column,
+            symbols,
+        }
+    }
+
+    pub fn value(&self) -> usize {
+        self.value
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The column of the number's first digit.
+    pub fn start_column(&self) -> usize {
+        self.start_column
+    }
+
+    /// The column of the number's last digit.
+    pub fn end_column(&self) -> usize {
+        self.end_column
+    }
+
+    /// The symbols adjacent to the number, ordered by position.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Whether the number is adjacent to at least one symbol.
+    pub fn is_part_number(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+}
+
+/// A number in the schematic that is adjacent to at least one symbol.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartNumber(Number);
+
+impl PartNumber {
+    pub fn value(&self) -> usize {
+        self.0.value()
+    }
+
+    pub fn row(&self) -> usize {
+        self.0.row()
+    }
+
+    /// The column of the number's first digit.
+    pub fn start_column(&self) -> usize {
+        self.0.start_column()
+    }
+
+    /// The column of the number's last digit.
+    pub fn end_column(&self) -> usize {
+        self.0.end_column()
+    }
+
+    /// The symbols adjacent to the number, ordered by position. There is always at least one.
+    pub fn symbols(&self) -> &[Symbol] {
+        self.0.symbols()
+    }
+}
+
+impl TryFrom<Number> for PartNumber {
+    type Error = Number;
+
+    /// Converts the number if it is a part number, or returns it unchanged otherwise.
+    fn try_from(number: Number) -> Result<Self, Self::Error> {
+        if number.is_part_number() {
+            Ok(Self(number))
+        } else {
+            Err(number)
+        }
+    }
+}
+
+/// What a cell of the schematic is, once part numbers and gears have been found.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CellKind {
+    Blank,
+    PartNumber,
+    /// A digit of a number that isn't adjacent to any symbol.
+    OtherNumber,
+    Symbol,
+    Gear,
+}
+
+/// The example schematic from the puzzle description.
+pub const EXAMPLE: &str = r#"467..114..
+...*......
+..35...633
+.......#..
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598.."#;
+
+/// A value in the schematic too large for a `usize`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OverflowError {
+    /// A number with too many digits, given by the cell of its first digit.
+    Number(Coord),
+    /// A gear whose part numbers combine to too large a value.
+    Gear(Coord),
+}
+
+impl OverflowError {
+    /// Returns the cell of the number's first digit, or of the gear.
+    pub fn coord(&self) -> Coord {
+        match self {
+            OverflowError::Number(coord) | OverflowError::Gear(coord) => *coord,
+        }
+    }
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OverflowError::Number(coord) => write!(f, "the number at {} is too large", coord),
+            OverflowError::Gear(coord) => {
+                write!(f, "the value of the gear at {} is too large", coord)
+            }
+        }
+    }
 }
 
 pub struct Schematic {
-    grid: Vec<Vec<char>>,
+    grid: Grid<char>,
+    /// How many cells of each row were given. Cells past the end of a short row are padding,
+    /// which is always blank whatever the classification.
+    row_lengths: Vec<usize>,
+    neighbourhood: Neighbourhood,
+    classification: Classification,
 }
 
 impl Schematic {
-    pub fn new(grid: Vec<Vec<char>>) -> Self {
-        Self { grid }
+    pub fn new(grid: Grid<char>) -> Self {
+        Self {
+            row_lengths: vec![grid.width(); grid.height()],
+            grid,
+            neighbourhood: Neighbourhood::default(),
+            classification: Classification::default(),
+        }
     }
 
+    /// Sets which cells count as adjacent, which is the 8 surrounding cells by default.
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
+    /// Parses a schematic with one row per line, padding any short rows with blank cells,
+    /// which are shown as '.'.
     pub fn parse_from_contents(contents: &str) -> Self {
-        let grid = contents
-            .lines()
-            .map(|line| line.chars().collect::<_>())
-            .collect::<_>();
+        let mut schematic = Self::new(Grid::parse(contents, '.', |character| character));
+        schematic.row_lengths = contents.lines().map(|line| line.chars().count()).collect();
+        schematic
+    }
+
+    /// Sets which characters are blanks, digits and symbols. By default, '.' is blank and
+    /// anything other than a digit is a symbol.
+    pub fn with_classification(mut self, classification: Classification) -> Self {
+        self.classification = classification;
+        self
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn neighbourhood(&self) -> &Neighbourhood {
+        &self.neighbourhood
+    }
 
-        Self::new(grid)
+    /// Replaces the character in a cell, returning the character it replaced. Setting a cell
+    /// past the end of a short row extends the row, so any padding before the cell becomes '.'.
+    ///
+    /// # Panics
+    /// Panics if the cell is outside the schematic.
+    pub fn set_cell(&mut self, coord: Coord, character: char) -> char {
+        let replaced = std::mem::replace(&mut self.grid[coord], character);
+
+        let length = &mut self.row_lengths[coord.row];
+        *length = (*length).max(coord.column + 1);
+        replaced
+    }
+
+    /// Inserts a row before the row at `index`, padding it with blank cells to the schematic's width.
+    ///
+    /// # Panics
+    /// Panics if the row is wider than the schematic, or `index` is past the last row.
+    pub fn insert_row(&mut self, index: usize, row: &str) {
+        let mut row = row.chars().collect::<Vec<_>>();
+        let length = row.len();
+        if row.len() < self.grid.width() {
+            row.resize(self.grid.width(), '.');
+        }
+
+        self.grid.insert_row(index, row);
+        self.row_lengths.insert(index, length);
+    }
+
+    /// Removes the row at `index`.
+    ///
+    /// # Panics
+    /// Panics if the row is outside the schematic.
+    pub fn remove_row(&mut self, index: usize) {
+        self.grid.remove_row(index);
+        self.row_lengths.remove(index);
+    }
+
+    /// Returns the character in a cell, or `None` if the cell is padding.
+    fn character(&self, coord: Coord) -> Option<char> {
+        (coord.column < self.row_lengths[coord.row]).then(|| self.grid[coord])
+    }
+
+    /// Classifies the character in a cell, or returns blank if the cell is padding.
+    fn cell_class(&self, coord: Coord) -> CellClass {
+        self.character(coord).map_or(CellClass::Blank, |character| {
+            self.classification.classify(character)
+        })
     }
 
     /// Returns a list of part numbers in the schematic, alongside the symbols that they are adjacent to.
-    /// A part number is a number that is not adjacent to any symbols.
+    /// A part number is a number that is adjacent to at least one symbol under the schematic's neighbourhood.
     /// Multiple digits in a row are considered a single part number.
-    pub fn get_part_numbers(&self) -> Vec<(usize, Vec<Symbol>)> {
-        let mut part_numbers = Vec::new();
-
-        let mut adjacent_indexes_set = HashSet::new();
-        let mut adjacent_symbols = HashSet::new();
-
-        for (row_index, row) in self.grid.iter().enumerate() {
-            let mut is_adjacent_to_symbol = false;
-            let mut current_part_number = String::new();
-
-            adjacent_symbols.clear();
-            current_part_number.clear();
-
-            for (column_index, character) in row.iter().enumerate() {
-                if character.is_ascii_digit() {
-                    current_part_number.push(*character);
-                } else if !current_part_number.is_empty() {
-                    if is_adjacent_to_symbol {
-                        part_numbers.push((
-                            current_part_number.parse().unwrap(),
-                            adjacent_symbols.clone().into_iter().collect::<Vec<_>>(),
-                        ));
-                        is_adjacent_to_symbol = false;
-                    }
-
-                    adjacent_symbols.clear();
-                    current_part_number.clear();
-                    continue;
-                }
-
-                // If we have a current part number, we should check grid adjacency for any non-digit non-'.' characters.
-                // We need to check all 8 adjacent cells, referenced as follows:
-                // TL T TR
-                //  L C R
-                // BL B BR
-                if !current_part_number.is_empty() {
-                    let adjacent_indexes = vec![
-                        (row_index.saturating_sub(1), column_index.saturating_sub(1)), // TL
-                        (row_index.saturating_sub(1), column_index),                   // T
-                        (row_index.saturating_sub(1), column_index.saturating_add(1)), // TR
-                        (row_index, column_index.saturating_sub(1)),                   // L
-                        (row_index, column_index.saturating_add(1)),                   // R
-                        (row_index.saturating_add(1), column_index.saturating_sub(1)), // BL
-                        (row_index.saturating_add(1), column_index),                   // B
-                        (row_index.saturating_add(1), column_index.saturating_add(1)), // BR
-                    ]
-                    .into_iter()
-                    .filter(|(row_index, column_index)| {
-                        *row_index < self.grid.len() && *column_index < self.grid[*row_index].len()
-                    });
-
-                    // if any of these overlap with the current index, or each other, we should omit them.
-                    adjacent_indexes_set.clear();
-                    adjacent_indexes_set.extend(adjacent_indexes);
-                    adjacent_indexes_set.remove(&(row_index, column_index));
-
-                    let current_adjacent_symbols = adjacent_indexes_set
-                        .iter()
-                        .filter(|(row_index, column_index)| {
-                            let character = self.grid[*row_index][*column_index];
-                            !character.is_ascii_digit() && character != '.'
-                        })
-                        .map(|(row_index, column_index)| Symbol {
-                            character: self.grid[*row_index][*column_index],
-                            row_index: *row_index,
-                            column_index: *column_index,
-                        })
-                        .collect::<Vec<_>>();
-
-                    is_adjacent_to_symbol =
-                        !current_adjacent_symbols.is_empty() || is_adjacent_to_symbol;
-
-                    adjacent_symbols.extend(current_adjacent_symbols);
-                }
-            }
+    /// Returns an error for the first number that is too large for a `usize`.
+    pub fn get_part_numbers(&self) -> Result<Vec<PartNumber>, OverflowError> {
+        Ok(self
+            .get_numbers()?
+            .into_iter()
+            .filter_map(|number| PartNumber::try_from(number).ok())
+            .collect())
+    }
 
-            if !current_part_number.is_empty() && is_adjacent_to_symbol {
-                part_numbers.push((
-                    current_part_number.parse().unwrap(),
-                    adjacent_symbols.clone().into_iter().collect::<Vec<_>>(),
-                ));
-            }
+    /// Returns every number in the schematic, including those that aren't part numbers
+    /// because they have no adjacent symbols.
+    pub fn get_numbers(&self) -> Result<Vec<Number>, OverflowError> {
+        let mut numbers = Vec::new();
+        for row_index in 0..self.grid.height() {
+            numbers.extend(self.get_numbers_in_row(row_index)?);
         }
-        part_numbers
+        Ok(numbers)
     }
 
-    /// Returns a list of gear ratios in the schematic.
-    /// A gear ratio is any '*' character adjacent to two part numbers, multiplied together.
-    pub fn get_gear_ratios(part_numbers: &[(usize, Vec<Symbol>)]) -> Vec<usize> {
-        let mut gear_ratios = Vec::new();
+    /// Returns every number on a row of the schematic, from left to right.
+    pub fn get_numbers_in_row(&self, row_index: usize) -> Result<Vec<Number>, OverflowError> {
+        let Some(row) = self.grid.row(row_index) else {
+            return Ok(Vec::new());
+        };
+
+        find_numbers_in_row(
+            &row[..self.row_lengths[row_index]],
+            row_index,
+            &self.classification,
+            |coord| {
+                self.grid
+                    .neighbours(coord, &self.neighbourhood)
+                    .filter_map(|neighbour| Some((neighbour, self.character(neighbour)?)))
+            },
+        )
+    }
 
-        let gears = part_numbers
+    /// Returns every symbol in the schematic, in row-major order.
+    pub fn get_symbols(&self) -> Vec<Symbol> {
+        self.grid
             .iter()
-            .flat_map(|(_, symbols)| symbols.iter())
-            .filter(|symbol| symbol.character == '*')
-            .collect::<Vec<_>>();
+            .filter(|(coord, _)| self.cell_class(*coord) == CellClass::Symbol)
+            .map(|(coord, character)| Symbol::new(*character, coord))
+            .collect()
+    }
 
+    /// Returns what each cell of the schematic is, given its part numbers and gears.
+    pub fn classify(&self, part_numbers: &[PartNumber], gears: &[Gear]) -> Grid<CellKind> {
+        let cells = self
+            .grid
+            .iter()
+            .map(|(coord, _)| match self.cell_class(coord) {
+                CellClass::Blank => CellKind::Blank,
+                CellClass::Digit => CellKind::OtherNumber,
+                CellClass::Symbol => CellKind::Symbol,
+            })
+            .collect();
+        let mut kinds = Grid::new(self.grid.width(), self.grid.height(), cells);
+
+        for part_number in part_numbers {
+            for column in part_number.start_column()..=part_number.end_column() {
+                kinds[Coord::new(part_number.row(), column)] = CellKind::PartNumber;
+            }
+        }
         for gear in gears {
-            let gear_row_index = gear.row_index;
-            let gear_column_index = gear.column_index;
+            kinds[gear.coord] = CellKind::Gear;
+        }
 
-            // Find all part numbers that reference this gear
-            let adjacent_part_numbers = part_numbers
-                .iter()
-                .filter(|(_, symbols)| {
-                    symbols.iter().any(|symbol| {
-                        symbol.row_index == gear_row_index
-                            && symbol.column_index == gear_column_index
-                    })
-                })
-                .map(|(part_number, _)| *part_number)
-                .collect::<Vec<_>>();
+        kinds
+    }
 
-            if adjacent_part_numbers.len() == 2 {
-                let gear_ratio = adjacent_part_numbers[0] * adjacent_part_numbers[1];
-                if !gear_ratios
-                    .iter()
-                    .any(|((x, y), _)| *x == gear_row_index && *y == gear_column_index)
-                {
-                    gear_ratios.push(((gear_row_index, gear_column_index), gear_ratio));
+    /// Returns the gears in the schematic, in the order they are first adjacent to a part number.
+    /// A gear is a symbol allowed by the rule that is adjacent to an allowed number of part numbers,
+    /// and its value combines those part numbers. The puzzle's rule is [`GearRule::default`].
+    /// Returns an error for the first gear whose value is too large for a `usize`.
+    pub fn get_gears(
+        part_numbers: &[PartNumber],
+        rule: &GearRule,
+    ) -> Result<Vec<Gear>, OverflowError> {
+        // Index the part numbers by the gear symbols they touch, keeping the symbols in the order
+        // they are first seen so that the gears are returned in a stable order.
+        let mut gear_symbols = Vec::new();
+        let mut adjacent_part_numbers: HashMap<Symbol, Vec<usize>> = HashMap::new();
+
+        for part_number in part_numbers {
+            for symbol in part_number.symbols() {
+                if !rule.is_gear_symbol(symbol.character()) {
+                    continue;
                 }
+
+                adjacent_part_numbers
+                    .entry(*symbol)
+                    .or_insert_with(|| {
+                        gear_symbols.push(*symbol);
+                        Vec::new()
+                    })
+                    .push(part_number.value());
             }
         }
 
-        gear_ratios
+        gear_symbols
+            .into_iter()
+            .filter(|symbol| rule.parts.contains(&adjacent_part_numbers[symbol].len()))
+            .map(|symbol| {
+                let values = &adjacent_part_numbers[&symbol];
+                Ok(Gear {
+                    coord: symbol.coord(),
+                    character: symbol.character(),
+                    value: rule
+                        .combine
+                        .apply(values.iter().copied())
+                        .ok_or(OverflowError::Gear(symbol.coord()))?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Finds the numbers on row `row_index` of a schematic, given the row's characters and a lookup
+/// returning the cells adjacent to a cell, with their characters. Any cell the lookup leaves out
+/// is treated as blank, so both [`Schematic`] and the streaming reader share this scan.
+/// Returns an error for the first number that is too large for a `usize`.
+pub fn find_numbers_in_row<I>(
+    row: &[char],
+    row_index: usize,
+    classification: &Classification,
+    adjacent_cells: impl Fn(Coord) -> I,
+) -> Result<Vec<Number>, OverflowError>
+where
+    I: IntoIterator<Item = (Coord, char)>,
+{
+    let mut numbers = Vec::new();
+
+    let mut column_index = 0;
+    while column_index < row.len() {
+        if !classification.is_digit(row[column_index]) {
+            column_index += 1;
+            continue;
+        }
+
+        let start_column = column_index;
+        while column_index < row.len() && classification.is_digit(row[column_index]) {
+            column_index += 1;
+        }
+        let end_column = column_index - 1;
+
+        // Check the cells adjacent to each digit for symbols,
+        // using a set as the neighbours of consecutive digits overlap.
+        let symbols = (start_column..=end_column)
+            .flat_map(|column| adjacent_cells(Coord::new(row_index, column)))
+            .filter(|(_, character)| classification.is_symbol(*character))
+            .map(|(coord, character)| Symbol::new(character, coord))
+            .collect::<BTreeSet<_>>();
+
+        // Only ASCII digits are ever classified as digits
+        let value = row[start_column..=end_column]
             .iter()
-            .map(|(_, gear_ratio)| *gear_ratio)
-            .collect::<Vec<_>>()
+            .try_fold(0usize, |value, digit| {
+                value
+                    .checked_mul(10)?
+                    .checked_add(digit.to_digit(10)? as usize)
+            })
+            .ok_or(OverflowError::Number(Coord::new(row_index, start_column)))?;
+
+        numbers.push(Number::new(
+            value,
+            row_index,
+            start_column,
+            end_column,
+            symbols.into_iter().collect(),
+        ));
     }
+
+    Ok(numbers)
 }
 
 #[cfg(test)]
 mod schematic_tests {
     use super::*;
-
-    const TEST_CONTENTS: &str = r#"467..114..
-...*......
-..35...633
-.......#..
-617*......
-.....+.58.
-..592.....
-......755.
-...$.*....
-.664.598.."#;
+    use crate::gear::Combine;
+    use crate::generator::{self, GeneratorConfig};
+    use grid::Adjacency;
+
+    fn part_number(
+        value: usize,
+        row: usize,
+        start_column: usize,
+        end_column: usize,
+        symbols: Vec<Symbol>,
+    ) -> PartNumber {
+        PartNumber::try_from(Number::new(value, row, start_column, end_column, symbols)).unwrap()
+    }
 
     #[test]
     fn test_parse_from_contents() {
-        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
 
-        assert_eq!(schematic.grid.len(), 10);
-        for row in schematic.grid {
+        assert_eq!(schematic.grid.height(), 10);
+        for row in schematic.grid.rows() {
             assert_eq!(row.len(), 10);
         }
     }
 
     #[test]
     fn test_get_part_numbers() {
-        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
 
-        let part_numbers = schematic.get_part_numbers();
+        let part_numbers = schematic.get_part_numbers().unwrap();
 
         assert_eq!(part_numbers.len(), 8);
 
         let expected = vec![
-            (
-                467,
-                vec![Symbol {
-                    character: '*',
-                    row_index: 1,
-                    column_index: 3,
-                }],
-            ),
-            (
-                35,
-                vec![Symbol {
-                    character: '*',
-                    row_index: 1,
-                    column_index: 3,
-                }],
-            ),
-            (
-                633,
-                vec![Symbol {
-                    character: '#',
-                    row_index: 3,
-                    column_index: 7,
-                }],
-            ),
-            (
-                617,
-                vec![Symbol {
-                    character: '*',
-                    row_index: 4,
-                    column_index: 3,
-                }],
-            ),
-            (
-                592,
-                vec![Symbol {
-                    character: '+',
-                    row_index: 5,
-                    column_index: 5,
-                }],
-            ),
-            (
-                755,
-                vec![Symbol {
-                    character: '*',
-                    row_index: 8,
-                    column_index: 5,
-                }],
-            ),
-            (
-                664,
-                vec![Symbol {
-                    character: '$',
-                    row_index: 8,
-                    column_index: 3,
-                }],
-            ),
-            (
-                598,
-                vec![Symbol {
-                    character: '*',
-                    row_index: 8,
-                    column_index: 5,
-                }],
-            ),
+            part_number(467, 0, 0, 2, vec![Symbol::new('*', Coord::new(1, 3))]),
+            part_number(35, 2, 2, 3, vec![Symbol::new('*', Coord::new(1, 3))]),
+            part_number(633, 2, 7, 9, vec![Symbol::new('#', Coord::new(3, 7))]),
+            part_number(617, 4, 0, 2, vec![Symbol::new('*', Coord::new(4, 3))]),
+            part_number(592, 6, 2, 4, vec![Symbol::new('+', Coord::new(5, 5))]),
+            part_number(755, 7, 6, 8, vec![Symbol::new('*', Coord::new(8, 5))]),
+            part_number(664, 9, 1, 3, vec![Symbol::new('$', Coord::new(8, 3))]),
+            part_number(598, 9, 5, 7, vec![Symbol::new('*', Coord::new(8, 5))]),
         ];
 
-        for (part_number, symbols) in &part_numbers {
-            assert!(expected.contains(&(*part_number, symbols.clone())));
-        }
-
         assert_eq!(part_numbers, expected);
     }
 
     #[test]
-    fn test_get_gear_ratios() {
-        let schematic = Schematic::parse_from_contents(TEST_CONTENTS);
+    fn test_part_number_spans() {
+        let schematic = Schematic::parse_from_contents("12.*\n..34\n5...");
+
+        let part_numbers = schematic.get_part_numbers().unwrap();
+        assert_eq!(part_numbers.len(), 1);
+
+        let part_number = &part_numbers[0];
+        assert_eq!(part_number.value(), 34);
+        assert_eq!(part_number.row(), 1);
+        assert_eq!(
+            (part_number.start_column(), part_number.end_column()),
+            (2, 3)
+        );
+        assert_eq!(part_number.symbols()[0].coord(), Coord::new(0, 3));
+    }
 
-        let part_numbers = schematic.get_part_numbers();
-        let gear_ratios = Schematic::get_gear_ratios(&part_numbers);
+    #[test]
+    fn test_get_numbers() {
+        let schematic = Schematic::parse_from_contents("12.*\n..34\n5...");
+
+        let numbers = schematic.get_numbers().unwrap();
+        assert_eq!(
+            numbers.iter().map(Number::value).collect::<Vec<_>>(),
+            vec![12, 34, 5]
+        );
+
+        let (part_numbers, others): (Vec<_>, Vec<_>) = numbers
+            .into_iter()
+            .map(PartNumber::try_from)
+            .partition(Result::is_ok);
+        assert_eq!(part_numbers.len(), 1);
+        assert!(others
+            .into_iter()
+            .all(|number| number.is_err_and(|number| number.symbols().is_empty())));
+    }
 
-        assert_eq!(gear_ratios.len(), 2);
-        assert_eq!(gear_ratios[0], 467 * 35);
-        assert_eq!(gear_ratios[1], 755 * 598);
+    #[test]
+    fn test_neighbourhoods() {
+        let part_number_sum = |neighbourhood| {
+            Schematic::parse_from_contents(EXAMPLE)
+                .with_neighbourhood(neighbourhood)
+                .get_part_numbers()
+                .unwrap()
+                .iter()
+                .map(PartNumber::value)
+                .sum::<usize>()
+        };
+
+        assert_eq!(part_number_sum(Neighbourhood::default()), 4361);
+
+        // 467, 592 and 755 only touch their symbols diagonally
+        let von_neumann = Neighbourhood::new(Adjacency::VonNeumann, false);
+        assert_eq!(part_number_sum(von_neumann), 4361 - 467 - 592 - 755);
+
+        // 114 and 58 are two cells away from a symbol
+        let radius = Neighbourhood::new(Adjacency::MooreRadius(2), false);
+        assert_eq!(part_number_sum(radius), 4361 + 114 + 58);
+    }
+
+    #[test]
+    fn test_wrapping_neighbourhood() {
+        let part_numbers = |wrap| {
+            Schematic::parse_from_contents("1..*\n....\n2..3")
+                .with_neighbourhood(Neighbourhood::new(Adjacency::Moore, wrap))
+                .get_part_numbers()
+                .unwrap()
+                .iter()
+                .map(PartNumber::value)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(part_numbers(false), Vec::<usize>::new());
+        // The '*' wraps around to touch every corner
+        assert_eq!(part_numbers(true), vec![1, 2, 3]);
+
+        let gears = Schematic::get_gears(
+            &Schematic::parse_from_contents("1..*\n....\n....\n...2")
+                .with_neighbourhood(Neighbourhood::new(Adjacency::Moore, true))
+                .get_part_numbers()
+                .unwrap(),
+            &GearRule::default(),
+        )
+        .unwrap();
+        assert_eq!(gears.len(), 1);
+        assert_eq!(gears[0].value, 2);
+    }
+
+    #[test]
+    fn test_classification() {
+        let contents = "12 ab\n..x #\n  3  ";
+        let part_numbers = |schematic: Schematic| {
+            schematic
+                .get_part_numbers()
+                .unwrap()
+                .iter()
+                .map(PartNumber::value)
+                .collect::<Vec<_>>()
+        };
+
+        // By default, spaces and letters are symbols
+        assert_eq!(
+            part_numbers(Schematic::parse_from_contents(contents)),
+            vec![12, 3]
+        );
+
+        let classification = Classification::parse("blanks = .\\s\nsymbols = #").unwrap();
+        let schematic =
+            Schematic::parse_from_contents(contents).with_classification(classification);
+        assert_eq!(
+            schematic.get_symbols(),
+            vec![Symbol::new('#', Coord::new(1, 4))]
+        );
+        assert!(part_numbers(schematic).is_empty());
+    }
+
+    #[test]
+    fn test_padding_is_blank() {
+        // Every character is a symbol, but the cells padding out the short rows aren't characters
+        let classification = Classification::parse("blanks =").unwrap();
+        let mut schematic =
+            Schematic::parse_from_contents("12\n\n3.4").with_classification(classification);
+
+        assert_eq!(
+            schematic.get_symbols(),
+            vec![Symbol::new('.', Coord::new(2, 1))]
+        );
+        assert_eq!(
+            schematic
+                .get_part_numbers()
+                .unwrap()
+                .iter()
+                .map(PartNumber::value)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+
+        // Setting a cell past the end of a row turns the padding before it into '.'
+        schematic.set_cell(Coord::new(1, 1), '5');
+        assert_eq!(schematic.get_symbols().len(), 2);
+        assert_eq!(schematic.get_part_numbers().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_get_gears() {
+        let schematic = Schematic::parse_from_contents(EXAMPLE);
+
+        let part_numbers = schematic.get_part_numbers().unwrap();
+        let gears = Schematic::get_gears(&part_numbers, &GearRule::default()).unwrap();
+
+        assert_eq!(gears.len(), 2);
+        assert_eq!(gears[0].coord, Coord::new(1, 3));
+        assert_eq!(gears[0].value, 467 * 35);
+        assert_eq!(gears[1].coord, Coord::new(8, 5));
+        assert_eq!(gears[1].value, 755 * 598);
+    }
+
+    #[test]
+    fn test_get_gears_with_rule() {
+        let schematic = Schematic::parse_from_contents("1.2.5.\n.#..*.\n3...4.");
+        let part_numbers = schematic.get_part_numbers().unwrap();
+
+        // The '#' touches three parts and the '*' touches two
+        let rule = GearRule::new(BTreeSet::from(['#', '*']), 3..=3, Combine::Sum);
+        let gears = Schematic::get_gears(&part_numbers, &rule).unwrap();
+        assert_eq!(
+            gears,
+            vec![Gear {
+                coord: Coord::new(1, 1),
+                character: '#',
+                value: 6,
+            }]
+        );
+
+        let rule = GearRule::new(BTreeSet::from(['#', '*']), 2..=3, Combine::Product);
+        let values = Schematic::get_gears(&part_numbers, &rule)
+            .unwrap()
+            .iter()
+            .map(|gear| gear.value)
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![6, 20]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let schematic = Schematic::parse_from_contents("1*\n.99999999999999999999");
+        assert_eq!(
+            schematic.get_part_numbers(),
+            Err(OverflowError::Number(Coord::new(1, 1)))
+        );
+
+        let contents = format!("{}*{}", usize::MAX, usize::MAX);
+        let part_numbers = Schematic::parse_from_contents(&contents)
+            .get_part_numbers()
+            .unwrap();
+        assert_eq!(
+            Schematic::get_gears(&part_numbers, &GearRule::default()),
+            Err(OverflowError::Gear(Coord::new(0, 20)))
+        );
+    }
+
+    /// Finds the gears by checking every gear symbol touching a part number against every part number.
+    fn brute_force_gears(schematic: &Schematic, rule: &GearRule) -> Vec<Gear> {
+        let part_numbers = schematic.get_part_numbers().unwrap();
+        let symbols = part_numbers
+            .iter()
+            .flat_map(PartNumber::symbols)
+            .copied()
+            .collect::<BTreeSet<_>>();
+
+        symbols
+            .into_iter()
+            .filter(|symbol| rule.is_gear_symbol(symbol.character()))
+            .filter_map(|symbol| {
+                let values = part_numbers
+                    .iter()
+                    .filter(|part_number| part_number.symbols().contains(&symbol))
+                    .map(PartNumber::value)
+                    .collect::<Vec<_>>();
+
+                rule.parts.contains(&values.len()).then(|| Gear {
+                    coord: symbol.coord(),
+                    character: symbol.character(),
+                    value: rule.combine.apply(values.into_iter()).unwrap(),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_get_gears_matches_brute_force() {
+        let rules = [
+            GearRule::default(),
+            GearRule::new(BTreeSet::from(['*', '#']), 1..=3, Combine::Sum),
+            GearRule::new(BTreeSet::from(['$']), 0..=1, Combine::Product),
+        ];
+
+        for seed in 0..5 {
+            let contents = generator::generate(&GeneratorConfig {
+                width: 40,
+                height: 40,
+                seed,
+                ..Default::default()
+            })
+            .unwrap()
+            .contents;
+
+            for neighbourhood in [
+                Neighbourhood::default(),
+                Neighbourhood::new(Adjacency::MooreRadius(2), true),
+            ] {
+                let schematic =
+                    Schematic::parse_from_contents(&contents).with_neighbourhood(neighbourhood);
+                for rule in &rules {
+                    let mut gears =
+                        Schematic::get_gears(&schematic.get_part_numbers().unwrap(), rule).unwrap();
+                    gears.sort_by_key(|gear| gear.coord);
+
+                    assert_eq!(gears, brute_force_gears(&schematic, rule));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[ignore = "parses a 10,000 x 10,000 schematic, so only runs with --ignored in release mode"]
+    fn test_large_schematic() {
+        let tile = EXAMPLE.lines().collect::<Vec<_>>();
+        let contents = (0..10_000)
+            .map(|row| tile[row % tile.len()].repeat(1_000))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let schematic = Schematic::parse_from_contents(&contents);
+        let part_numbers = schematic.get_part_numbers().unwrap();
+        let gears = Schematic::get_gears(&part_numbers, &GearRule::default()).unwrap();
+
+        // Each copy of the example has 8 part numbers and 2 gears, and the copies don't touch
+        assert_eq!(part_numbers.len(), 8_000_000);
+        assert_eq!(gears.len(), 2_000_000);
+        assert_eq!(
+            gears.iter().map(|gear| gear.value).sum::<usize>(),
+            467_835 * 1_000_000
+        );
     }
 }
@@ -0,0 +1,396 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use grid::Neighbourhood;
+
+use crate::classification::Classification;
+use crate::gear::{Gear, GearRule};
+use crate::schematic::{self, OverflowError, PartNumber, Symbol};
+use crate::validation::{SchematicValidator, ValidationError};
+
+/// Something found while streaming a schematic.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event {
+    PartNumber(PartNumber),
+    Gear(Gear),
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// Only neighbourhoods within one row of a cell, that don't wrap, can be streamed.
+    UnsupportedNeighbourhood(Neighbourhood),
+    /// The problems with the first row to fail validation.
+    Invalid(Vec<ValidationError>),
+    Overflow(OverflowError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "failed to read the schematic: {}", err),
+            StreamError::UnsupportedNeighbourhood(neighbourhood) => write!(
+                f,
+                "{:?} can't be streamed, as it reaches more than one row away or wraps",
+                neighbourhood
+            ),
+            StreamError::Invalid(errors) => {
+                write!(f, "invalid schematic:")?;
+                for error in errors {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+            StreamError::Overflow(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Finds part numbers and gears while reading a schematic one row at a time, keeping only
+/// three rows in memory. It finds the same part numbers and gears as [`crate::schematic::Schematic`].
+pub struct SchematicStream {
+    classification: Classification,
+    offsets: Vec<(isize, isize)>,
+    gear_rule: GearRule,
+    validator: Option<SchematicValidator>,
+}
+
+impl SchematicStream {
+    pub fn new(
+        classification: Classification,
+        neighbourhood: Neighbourhood,
+        gear_rule: GearRule,
+    ) -> Result<Self, StreamError> {
+        if neighbourhood.wrap || neighbourhood.radius() > 1 {
+            return Err(StreamError::UnsupportedNeighbourhood(neighbourhood));
+        }
+
+        Ok(Self {
+            classification,
+            offsets: neighbourhood.offsets().collect(),
+            gear_rule,
+            validator: None,
+        })
+    }
+
+    /// Checks each row as it is read, stopping at the first row with any problems.
+    /// Rows are expected to be as wide as the first row.
+    pub fn with_validator(mut self, validator: SchematicValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Reads the schematic, calling `on_event` with each part number once the rows either side
+    /// of it are read, and each gear once every row that could touch it is read.
+    pub fn process(
+        &self,
+        reader: impl BufRead,
+        mut on_event: impl FnMut(Event),
+    ) -> Result<(), StreamError> {
+        // The previous, current and next rows
+        let mut window: VecDeque<Vec<char>> = VecDeque::with_capacity(3);
+        let mut gears = PendingGears::default();
+        let mut row_index = 0;
+        let mut width = None;
+
+        let mut lines = reader.lines().enumerate();
+        loop {
+            let line = lines
+                .next()
+                .map(|(index, line)| line.map(|line| (index, line)))
+                .transpose()
+                .map_err(StreamError::Io)?;
+            let at_end = line.is_none();
+            if let Some((index, line)) = line {
+                if let Some(validator) = &self.validator {
+                    let width = *width.get_or_insert(line.chars().count());
+                    let errors = validator.validate_row(index + 1, &line, width);
+                    if !errors.is_empty() {
+                        return Err(StreamError::Invalid(errors));
+                    }
+                }
+
+                window.push_back(line.chars().collect());
+            }
+
+            // The current row is the middle of the window, or the first row if there is no previous
+            // row yet. It can only be processed once the next row is read, or there are no more rows.
+            let current = if row_index == 0 { 0 } else { 1 };
+            if window.len() > current + 1 || (at_end && window.len() > current) {
+                for part_number in self.find_part_numbers(&window, current, row_index)? {
+                    gears.add(&part_number, &self.gear_rule);
+                    on_event(Event::PartNumber(part_number));
+                }
+
+                // Gears on the previous row can't be touched by any more part numbers
+                if let Some(previous_row) = row_index.checked_sub(1) {
+                    gears.complete(previous_row, &self.gear_rule, &mut on_event)?;
+                }
+
+                row_index += 1;
+                if window.len() == 3 {
+                    window.pop_front();
+                }
+            }
+
+            if at_end {
+                gears.complete(usize::MAX, &self.gear_rule, &mut on_event)?;
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns the part numbers on the `current` row of the window, which is row `row_index` of the schematic.
+    fn find_part_numbers(
+        &self,
+        window: &VecDeque<Vec<char>>,
+        current: usize,
+        row_index: usize,
+    ) -> Result<Vec<PartNumber>, StreamError> {
+        // The first row of the window is this row of the schematic
+        let first_row = row_index - current;
+
+        let numbers = schematic::find_numbers_in_row(
+            &window[current],
+            row_index,
+            &self.classification,
+            |coord| {
+                self.offsets.iter().filter_map(move |(rows, columns)| {
+                    let neighbour = coord.offset(*rows, *columns)?;
+                    let row = window.get(neighbour.row.checked_sub(first_row)?)?;
+                    Some((neighbour, *row.get(neighbour.column)?))
+                })
+            },
+        )
+        .map_err(StreamError::Overflow)?;
+
+        Ok(numbers
+            .into_iter()
+            .filter_map(|number| PartNumber::try_from(number).ok())
+            .collect())
+    }
+}
+
+/// The gear symbols seen so far that may still be touched by more part numbers.
+#[derive(Default)]
+struct PendingGears {
+    /// The symbols in the order they were first seen.
+    symbols: Vec<Symbol>,
+    values: HashMap<Symbol, Vec<usize>>,
+}
+
+impl PendingGears {
+    fn add(&mut self, part_number: &PartNumber, rule: &GearRule) {
+        for symbol in part_number.symbols() {
+            if !rule.is_gear_symbol(symbol.character()) {
+                continue;
+            }
+
+            self.values
+                .entry(*symbol)
+                .or_insert_with(|| {
+                    self.symbols.push(*symbol);
+                    Vec::new()
+                })
+                .push(part_number.value());
+        }
+    }
+
+    /// Emits the gears on or above `last_row` that satisfy the rule, and forgets them.
+    fn complete(
+        &mut self,
+        last_row: usize,
+        rule: &GearRule,
+        on_event: &mut impl FnMut(Event),
+    ) -> Result<(), StreamError> {
+        let (complete, pending) = self
+            .symbols
+            .iter()
+            .partition::<Vec<Symbol>, _>(|symbol| symbol.coord().row <= last_row);
+        self.symbols = pending;
+
+        for symbol in complete {
+            let values = self.values.remove(&symbol).unwrap_or_default();
+            if rule.parts.contains(&values.len()) {
+                let value = rule
+                    .combine
+                    .apply(values.into_iter())
+                    .ok_or(StreamError::Overflow(OverflowError::Gear(symbol.coord())))?;
+                on_event(Event::Gear(Gear {
+                    coord: symbol.coord(),
+                    character: symbol.character(),
+                    value,
+                }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod stream_tests {
+    use super::*;
+    use crate::schematic::{Number, Schematic, EXAMPLE};
+    use grid::{Adjacency, Coord};
+
+    /// Streams the contents, returning the part numbers and the gears sorted by position.
+    fn stream(contents: &str, neighbourhood: Neighbourhood) -> (Vec<PartNumber>, Vec<Gear>) {
+        let stream = SchematicStream::new(
+            Classification::default(),
+            neighbourhood,
+            GearRule::default(),
+        )
+        .unwrap();
+
+        let mut part_numbers = Vec::new();
+        let mut gears = Vec::new();
+        stream
+            .process(contents.as_bytes(), |event| match event {
+                Event::PartNumber(part_number) => part_numbers.push(part_number),
+                Event::Gear(gear) => gears.push(gear),
+            })
+            .unwrap();
+
+        gears.sort_by_key(|gear| gear.coord);
+        (part_numbers, gears)
+    }
+
+    fn in_memory(contents: &str, neighbourhood: Neighbourhood) -> (Vec<PartNumber>, Vec<Gear>) {
+        let schematic = Schematic::parse_from_contents(contents).with_neighbourhood(neighbourhood);
+        let part_numbers = schematic.get_part_numbers().unwrap();
+        let mut gears = Schematic::get_gears(&part_numbers, &GearRule::default()).unwrap();
+
+        gears.sort_by_key(|gear| gear.coord);
+        (part_numbers, gears)
+    }
+
+    #[test]
+    fn test_matches_in_memory() {
+        for contents in [EXAMPLE, "", "1*2", "1\n*\n2", "..\n12*\n3"] {
+            for neighbourhood in [
+                Neighbourhood::default(),
+                Neighbourhood::new(Adjacency::VonNeumann, false),
+            ] {
+                assert_eq!(
+                    stream(contents, neighbourhood),
+                    in_memory(contents, neighbourhood),
+                    "{:?}",
+                    contents
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tall_schematic() {
+        // Shift each copy of the example so the rows differ, and join them so that
+        // gears and numbers touch across the copies
+        let contents = (0..500)
+            .flat_map(|copy| {
+                EXAMPLE
+                    .lines()
+                    .map(move |line| format!("{}{}", ".".repeat(copy % 3), line))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let (part_numbers, gears) = stream(&contents, Neighbourhood::default());
+        assert_eq!(
+            (part_numbers, gears),
+            in_memory(&contents, Neighbourhood::default())
+        );
+    }
+
+    #[test]
+    fn test_gears_emitted_when_complete() {
+        let stream = SchematicStream::new(
+            Classification::default(),
+            Neighbourhood::default(),
+            GearRule::default(),
+        )
+        .unwrap();
+
+        let mut events = Vec::new();
+        stream
+            .process("2*3\n...\n4*5\n...".as_bytes(), |event| events.push(event))
+            .unwrap();
+
+        // Each gear is emitted as soon as the row below it has been read
+        let kinds = events
+            .iter()
+            .map(|event| match event {
+                Event::PartNumber(part_number) => part_number.value(),
+                Event::Gear(gear) => gear.value,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec![2, 3, 6, 4, 5, 20]);
+    }
+
+    #[test]
+    fn test_validation() {
+        let stream = SchematicStream::new(
+            Classification::default(),
+            Neighbourhood::default(),
+            GearRule::default(),
+        )
+        .unwrap()
+        .with_validator(SchematicValidator::default());
+
+        let mut events = Vec::new();
+        let err = stream
+            .process("1*.\n...\n2 3\n..".as_bytes(), |event| events.push(event))
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "invalid schematic:\n  line 3, column 2: unexpected whitespace ' '"
+        );
+        // Only the rows before the invalid row have been processed
+        let part_number = Number::new(1, 0, 0, 0, vec![Symbol::new('*', Coord::new(0, 1))]);
+        assert_eq!(
+            events,
+            vec![Event::PartNumber(part_number.try_into().unwrap())]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let stream = SchematicStream::new(
+            Classification::default(),
+            Neighbourhood::default(),
+            GearRule::default(),
+        )
+        .unwrap();
+
+        let err = stream
+            .process("..\n*.\n.99999999999999999999".as_bytes(), |_| {})
+            .unwrap_err();
+        assert_eq!(err.to_string(), "the number at (2, 1) is too large");
+
+        let contents = format!("{}*{}", usize::MAX, usize::MAX);
+        let err = stream.process(contents.as_bytes(), |_| {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the value of the gear at (0, 20) is too large"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_neighbourhoods() {
+        for neighbourhood in [
+            Neighbourhood::new(Adjacency::MooreRadius(2), false),
+            Neighbourhood::new(Adjacency::Moore, true),
+        ] {
+            assert!(matches!(
+                SchematicStream::new(
+                    Classification::default(),
+                    neighbourhood,
+                    GearRule::default()
+                ),
+                Err(StreamError::UnsupportedNeighbourhood(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::classification::CharacterSet;
+use crate::schematic::Schematic;
+
+/// What to do with rows that aren't as wide as the first row.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RaggedRows {
+    #[default]
+    Reject,
+    /// Pad every row with blank cells to the width of the widest row.
+    Pad,
+}
+
+impl FromStr for RaggedRows {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(RaggedRows::Reject),
+            "pad" => Ok(RaggedRows::Pad),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Issue {
+    /// A row with a different width to the first row.
+    RaggedRow { expected: usize, found: usize },
+    /// A whitespace character that isn't in the alphabet, such as a tab.
+    Whitespace(char),
+    /// Any other character that isn't in the alphabet.
+    UnexpectedCharacter(char),
+}
+
+/// A problem with a schematic, at a 1-based line and column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ValidationError {
+    pub line: usize,
+    pub column: usize,
+    pub issue: Issue,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+
+        match &self.issue {
+            Issue::RaggedRow { expected, found } => write!(
+                f,
+                "expected a row of {} cells but found {}",
+                expected, found
+            ),
+            Issue::Whitespace(character) => {
+                write!(f, "unexpected whitespace {:?}", character)
+            }
+            Issue::UnexpectedCharacter(character) => {
+                write!(f, "{:?} is not in the alphabet", character)
+            }
+        }
+    }
+}
+
+/// Checks a schematic's text before parsing it, rather than quietly padding short rows
+/// and treating any unexpected character as a symbol.
+#[derive(Clone, Debug)]
+pub struct SchematicValidator {
+    /// The characters a schematic may contain.
+    pub alphabet: CharacterSet,
+    pub ragged_rows: RaggedRows,
+}
+
+impl Default for SchematicValidator {
+    /// Allows any printable ASCII character other than a space, and rejects ragged rows.
+    fn default() -> Self {
+        Self {
+            alphabet: CharacterSet::Matching(|c| c.is_ascii_graphic()),
+            ragged_rows: RaggedRows::Reject,
+        }
+    }
+}
+
+impl SchematicValidator {
+    /// Returns every problem with the schematic's text, in order.
+    pub fn validate(&self, contents: &str) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let mut expected_width = None;
+
+        for (index, line) in contents.lines().enumerate() {
+            let expected = *expected_width.get_or_insert(line.chars().count());
+            errors.extend(self.validate_row(index + 1, line, expected));
+        }
+
+        errors
+    }
+
+    /// Returns every problem with a row on a 1-based line of a schematic `width` cells wide.
+    pub fn validate_row(&self, line: usize, row: &str, width: usize) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        let found = row.chars().count();
+        if self.ragged_rows == RaggedRows::Reject && found != width {
+            errors.push(ValidationError {
+                line,
+                column: found.min(width) + 1,
+                issue: Issue::RaggedRow {
+                    expected: width,
+                    found,
+                },
+            });
+        }
+
+        for (column, character) in row.chars().enumerate() {
+            errors.extend(self.validate_character(line, column + 1, character));
+        }
+
+        errors
+    }
+
+    /// Returns the problem with a character at a 1-based line and column, if it isn't in the alphabet.
+    pub fn validate_character(
+        &self,
+        line: usize,
+        column: usize,
+        character: char,
+    ) -> Option<ValidationError> {
+        if self.alphabet.contains(character) {
+            return None;
+        }
+
+        Some(ValidationError {
+            line,
+            column,
+            issue: if character.is_whitespace() {
+                Issue::Whitespace(character)
+            } else {
+                Issue::UnexpectedCharacter(character)
+            },
+        })
+    }
+
+    /// Parses the schematic if it has no problems, or returns all of them.
+    pub fn parse(&self, contents: &str) -> Result<Schematic, Vec<ValidationError>> {
+        let errors = self.validate(contents);
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(Schematic::parse_from_contents(contents))
+    }
+}
+
+#[cfg(test)]
+mod validation_tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_schematic() {
+        let validator = SchematicValidator::default();
+
+        assert!(validator.validate("467..114..\n...*......\r\n").is_empty());
+        assert!(validator.parse("").is_ok());
+    }
+
+    #[test]
+    fn test_ragged_rows() {
+        let contents = "467..\n...*\n..35...";
+
+        let errors = SchematicValidator::default().validate(contents);
+        assert_eq!(
+            errors
+                .iter()
+                .map(ValidationError::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "line 2, column 5: expected a row of 5 cells but found 4",
+                "line 3, column 6: expected a row of 5 cells but found 7"
+            ]
+        );
+
+        let validator = SchematicValidator {
+            ragged_rows: RaggedRows::Pad,
+            ..Default::default()
+        };
+        let schematic = validator.parse(contents).unwrap();
+        assert_eq!(schematic.grid().width(), 7);
+        assert_eq!(
+            schematic.grid().row(1),
+            Some(&['.', '.', '.', '*', '.', '.', '.'][..])
+        );
+    }
+
+    #[test]
+    fn test_unexpected_characters() {
+        let errors = SchematicValidator::default().validate("12\t.\n.é *");
+
+        assert_eq!(
+            errors,
+            vec![
+                ValidationError {
+                    line: 1,
+                    column: 3,
+                    issue: Issue::Whitespace('\t')
+                },
+                ValidationError {
+                    line: 2,
+                    column: 2,
+                    issue: Issue::UnexpectedCharacter('é')
+                },
+                ValidationError {
+                    line: 2,
+                    column: 3,
+                    issue: Issue::Whitespace(' ')
+                },
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "line 2, column 2: 'é' is not in the alphabet"
+        );
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let validator = SchematicValidator {
+            alphabet: CharacterSet::parse("0123456789.*\\s").unwrap(),
+            ragged_rows: RaggedRows::Reject,
+        };
+
+        assert!(validator.validate("12 *\n..3.").is_empty());
+        assert_eq!(
+            validator.validate("12#*")[0].issue,
+            Issue::UnexpectedCharacter('#')
+        );
+    }
+
+    #[test]
+    fn test_validate_row() {
+        let validator = SchematicValidator::default();
+
+        assert!(validator.validate_row(3, "..*.", 4).is_empty());
+        assert_eq!(
+            validator.validate_row(3, "..", 4),
+            vec![ValidationError {
+                line: 3,
+                column: 3,
+                issue: Issue::RaggedRow {
+                    expected: 4,
+                    found: 2
+                }
+            }]
+        );
+        assert_eq!(
+            validator.validate_character(3, 2, '\t').unwrap().issue,
+            Issue::Whitespace('\t')
+        );
+
+        let padding = SchematicValidator {
+            ragged_rows: RaggedRows::Pad,
+            ..Default::default()
+        };
+        assert!(padding.validate_row(3, "..", 4).is_empty());
+    }
+}
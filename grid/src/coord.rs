@@ -0,0 +1,44 @@
+use std::fmt;
+
+/// A position in a [`crate::Grid`], counted from the top-left cell.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Coord {
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Coord {
+    pub fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+
+    /// Returns the coordinate offset by the given number of rows and columns,
+    /// or `None` if it would be above or left of the grid.
+    pub fn offset(&self, rows: isize, columns: isize) -> Option<Coord> {
+        Some(Coord::new(
+            self.row.checked_add_signed(rows)?,
+            self.column.checked_add_signed(columns)?,
+        ))
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.column)
+    }
+}
+
+#[cfg(test)]
+mod coord_tests {
+    use super::*;
+
+    #[test]
+    fn test_offset() {
+        let coord = Coord::new(1, 2);
+
+        assert_eq!(coord.offset(-1, 1), Some(Coord::new(0, 3)));
+        assert_eq!(coord.offset(0, -2), Some(Coord::new(1, 0)));
+        assert_eq!(coord.offset(-2, 0), None);
+        assert_eq!(coord.offset(0, -3), None);
+    }
+}
@@ -0,0 +1,470 @@
+use std::ops::{Index, IndexMut};
+
+use crate::neighbourhood::{ALL_OFFSETS, ORTHOGONAL_OFFSETS};
+use crate::{Coord, Neighbourhood};
+
+/// A rectangular grid of cells, stored row by row in a single [`Vec`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from its cells in row-major order.
+    ///
+    /// # Panics
+    /// Panics if there are not exactly `width * height` cells.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(
+            cells.len(),
+            width * height,
+            "a {}x{} grid needs {} cells",
+            width,
+            height,
+            width * height
+        );
+
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Creates a grid from a list of rows, padding any shorter rows with `fill`
+    /// so that every row is as wide as the widest.
+    pub fn from_rows(rows: Vec<Vec<T>>, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let height = rows.len();
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut cells = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            cells.extend(row);
+        }
+
+        Self::new(width, height, cells)
+    }
+
+    /// Parses a grid from text, with one row per line and one cell per character.
+    /// Lines shorter than the longest are padded with `fill`.
+    pub fn parse(contents: &str, fill: T, mut parse_cell: impl FnMut(char) -> T) -> Self
+    where
+        T: Clone,
+    {
+        let rows = contents
+            .lines()
+            .map(|line| line.chars().map(&mut parse_cell).collect())
+            .collect();
+
+        Self::from_rows(rows, fill)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if the coordinate lies within the grid.
+    pub fn contains(&self, coord: Coord) -> bool {
+        coord.row < self.height && coord.column < self.width
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.contains(coord)
+            .then(|| &self.cells[coord.row * self.width + coord.column])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        if self.contains(coord) {
+            Some(&mut self.cells[coord.row * self.width + coord.column])
+        } else {
+            None
+        }
+    }
+
+    /// Inserts a row before the row at `index`, moving every row below it down.
+    ///
+    /// # Panics
+    /// Panics if the row isn't as wide as the grid, or `index` is past the last row.
+    pub fn insert_row(&mut self, index: usize, row: Vec<T>) {
+        assert_eq!(
+            row.len(),
+            self.width,
+            "a row of the grid must have {} cells",
+            self.width
+        );
+        assert!(
+            index <= self.height,
+            "can't insert row {} into {} rows",
+            index,
+            self.height
+        );
+
+        let start = index * self.width;
+        self.cells.splice(start..start, row);
+        self.height += 1;
+    }
+
+    /// Removes and returns the row at `index`, moving every row below it up.
+    ///
+    /// # Panics
+    /// Panics if the row is out of bounds.
+    pub fn remove_row(&mut self, index: usize) -> Vec<T> {
+        assert!(
+            index < self.height,
+            "can't remove row {} from {} rows",
+            index,
+            self.height
+        );
+
+        let start = index * self.width;
+        self.height -= 1;
+        self.cells.drain(start..start + self.width).collect()
+    }
+
+    /// Returns the in-bounds orthogonal neighbours of a cell (the von Neumann neighbourhood).
+    pub fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.neighbours_at(coord, &ORTHOGONAL_OFFSETS)
+    }
+
+    /// Returns the in-bounds orthogonal and diagonal neighbours of a cell (the Moore neighbourhood).
+    pub fn neighbours8(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        self.neighbours_at(coord, &ALL_OFFSETS)
+    }
+
+    /// Returns the in-bounds neighbours of a cell under the given rule. When the rule wraps,
+    /// the same cell may be returned more than once if the grid is smaller than the neighbourhood.
+    pub fn neighbours(
+        &self,
+        coord: Coord,
+        neighbourhood: &Neighbourhood,
+    ) -> impl Iterator<Item = Coord> + '_ {
+        let wrap = neighbourhood.wrap;
+
+        neighbourhood
+            .offsets()
+            .filter_map(move |(rows, columns)| {
+                if wrap {
+                    self.wrapping_offset(coord, rows, columns)
+                } else {
+                    coord.offset(rows, columns)
+                }
+            })
+            .filter(move |neighbour| self.contains(*neighbour) && *neighbour != coord)
+    }
+
+    /// Offsets a coordinate, wrapping around the edges of the grid.
+    fn wrapping_offset(&self, coord: Coord, rows: isize, columns: isize) -> Option<Coord> {
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        let wrap = |index: usize, offset: isize, len: usize| {
+            (index as isize + offset).rem_euclid(len as isize) as usize
+        };
+        Some(Coord::new(
+            wrap(coord.row, rows, self.height),
+            wrap(coord.column, columns, self.width),
+        ))
+    }
+
+    fn neighbours_at<'a>(
+        &'a self,
+        coord: Coord,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = Coord> + 'a {
+        offsets
+            .iter()
+            .filter_map(move |(rows, columns)| coord.offset(*rows, *columns))
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// Returns every cell alongside its coordinate, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (Coord::new(index / self.width, index % self.width), cell))
+    }
+
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// Returns each row of the grid as a slice, from top to bottom.
+    /// A grid with no columns still has one (empty) row for each row of its height.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// Returns the cells of a column from top to bottom, or nothing if it is out of bounds.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        let cells = if column < self.width {
+            &self.cells[column..]
+        } else {
+            &[]
+        };
+
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    /// Returns the cells within the rectangle between two corners (inclusive),
+    /// clipped to the grid, in row-major order.
+    pub fn region(
+        &self,
+        top_left: Coord,
+        bottom_right: Coord,
+    ) -> impl Iterator<Item = (Coord, &T)> {
+        let rows = top_left.row..=bottom_right.row.min(self.height.saturating_sub(1));
+        let columns = top_left.column..=bottom_right.column.min(self.width.saturating_sub(1));
+
+        rows.filter(|row| *row < self.height).flat_map(move |row| {
+            columns
+                .clone()
+                .filter(|column| *column < self.width)
+                .map(move |column| {
+                    let coord = Coord::new(row, column);
+                    (coord, &self[coord])
+                })
+        })
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    /// # Panics
+    /// Panics if the coordinate is out of bounds, use [`Grid::get`] to handle this.
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{} is outside the grid", coord))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{} is outside the grid", coord))
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::*;
+    use crate::Adjacency;
+
+    const TEST_CONTENTS: &str = "abc\ndef\nghi\njkl";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(TEST_CONTENTS, '.', |c| c)
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(grid[Coord::new(0, 0)], 'a');
+        assert_eq!(grid[Coord::new(3, 2)], 'l');
+    }
+
+    #[test]
+    fn test_parse_pads_ragged_rows() {
+        let grid = Grid::parse("ab\nc\n\ndef", '.', |c| c);
+
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 4);
+        assert_eq!(
+            grid.rows()
+                .map(|row| row.iter().collect())
+                .collect::<Vec<String>>(),
+            vec!["ab.", "c..", "...", "def"]
+        );
+    }
+
+    #[test]
+    fn test_parse_cells() {
+        let grid = Grid::parse("12\n3", 0, |c| c.to_digit(10).unwrap());
+
+        assert_eq!(grid, Grid::new(2, 2, vec![1, 2, 3, 0]));
+    }
+
+    #[test]
+    fn test_bounds_checked_access() {
+        let mut grid = grid();
+
+        assert_eq!(grid.get(Coord::new(1, 1)), Some(&'e'));
+        assert_eq!(grid.get(Coord::new(1, 3)), None);
+        assert_eq!(grid.get(Coord::new(4, 0)), None);
+
+        *grid.get_mut(Coord::new(1, 1)).unwrap() = 'E';
+        grid[Coord::new(0, 0)] = 'A';
+        assert_eq!(grid.row(0), Some(&['A', 'b', 'c'][..]));
+        assert_eq!(grid.row(1), Some(&['d', 'E', 'f'][..]));
+        assert_eq!(grid.row(4), None);
+        assert!(grid.get_mut(Coord::new(0, 3)).is_none());
+    }
+
+    #[test]
+    fn test_insert_and_remove_rows() {
+        let mut grid = grid();
+
+        grid.insert_row(1, vec!['x', 'y', 'z']);
+        grid.insert_row(5, vec!['m', 'n', 'o']);
+        assert_eq!(grid.height(), 6);
+        assert_eq!(grid.column(0).collect::<String>(), "axdgjm");
+
+        assert_eq!(grid.remove_row(0), vec!['a', 'b', 'c']);
+        assert_eq!(grid.height(), 5);
+        assert_eq!(grid[Coord::new(0, 2)], 'z');
+        assert_eq!(grid.row(4), Some(&['m', 'n', 'o'][..]));
+    }
+
+    #[test]
+    #[should_panic(expected = "a row of the grid must have 3 cells")]
+    fn test_insert_row_checks_width() {
+        grid().insert_row(0, vec!['a']);
+    }
+
+    #[test]
+    #[should_panic(expected = "(0, 3) is outside the grid")]
+    fn test_index_out_of_bounds() {
+        let _ = grid()[Coord::new(0, 3)];
+    }
+
+    #[test]
+    #[should_panic(expected = "a 2x2 grid needs 4 cells")]
+    fn test_new_checks_size() {
+        Grid::new(2, 2, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_neighbours4() {
+        let grid = grid();
+
+        let neighbours = grid.neighbours4(Coord::new(1, 1)).collect::<Vec<_>>();
+        assert_eq!(
+            neighbours,
+            vec![
+                Coord::new(0, 1),
+                Coord::new(1, 0),
+                Coord::new(1, 2),
+                Coord::new(2, 1)
+            ]
+        );
+
+        let neighbours = grid.neighbours4(Coord::new(0, 0)).collect::<Vec<_>>();
+        assert_eq!(neighbours, vec![Coord::new(0, 1), Coord::new(1, 0)]);
+    }
+
+    #[test]
+    fn test_neighbours8() {
+        let grid = grid();
+
+        assert_eq!(grid.neighbours8(Coord::new(1, 1)).count(), 8);
+
+        let neighbours = grid.neighbours8(Coord::new(3, 2)).collect::<Vec<_>>();
+        assert_eq!(
+            neighbours,
+            vec![Coord::new(2, 1), Coord::new(2, 2), Coord::new(3, 1)]
+        );
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+
+        let moore = Neighbourhood::default();
+        assert!(grid
+            .neighbours(Coord::new(3, 2), &moore)
+            .eq(grid.neighbours8(Coord::new(3, 2))));
+
+        let wrapping = Neighbourhood::new(Adjacency::VonNeumann, true);
+        let neighbours = grid
+            .neighbours(Coord::new(0, 0), &wrapping)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            neighbours,
+            vec![
+                Coord::new(3, 0),
+                Coord::new(0, 2),
+                Coord::new(0, 1),
+                Coord::new(1, 0)
+            ]
+        );
+
+        let radius = Neighbourhood::new(Adjacency::MooreRadius(2), false);
+        assert_eq!(grid.neighbours(Coord::new(0, 0), &radius).count(), 8);
+        assert_eq!(grid.neighbours(Coord::new(2, 1), &radius).count(), 11);
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = grid();
+
+        let (coord, cell) = grid.iter().nth(4).unwrap();
+        assert_eq!((coord, *cell), (Coord::new(1, 1), 'e'));
+        assert_eq!(grid.rows().count(), 4);
+        assert_eq!(grid.column(1).collect::<String>(), "behk");
+        assert_eq!(grid.column(3).count(), 0);
+    }
+
+    #[test]
+    fn test_region() {
+        let grid = grid();
+
+        let region = grid
+            .region(Coord::new(1, 1), Coord::new(2, 2))
+            .map(|(_, cell)| *cell)
+            .collect::<String>();
+        assert_eq!(region, "efhi");
+
+        // Regions are clipped to the grid
+        let region = grid
+            .region(Coord::new(2, 1), Coord::new(10, 10))
+            .map(|(coord, _)| coord)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            region,
+            vec![
+                Coord::new(2, 1),
+                Coord::new(2, 2),
+                Coord::new(3, 1),
+                Coord::new(3, 2)
+            ]
+        );
+        assert_eq!(grid.region(Coord::new(5, 5), Coord::new(6, 6)).count(), 0);
+    }
+
+    #[test]
+    fn test_empty_grid() {
+        let grid = Grid::parse("", '.', |c| c);
+
+        assert_eq!(grid.width(), 0);
+        assert_eq!(grid.height(), 0);
+        assert_eq!(grid.rows().count(), 0);
+        assert_eq!(grid.column(0).count(), 0);
+        assert_eq!(grid.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_zero_width_grid() {
+        let grid = Grid::parse("\n\n", '.', |c| c);
+
+        assert_eq!((grid.width(), grid.height()), (0, 2));
+        assert_eq!(grid.rows().collect::<Vec<_>>(), vec![&[] as &[char]; 2]);
+        assert_eq!(grid.row(1), Some(&[] as &[char]));
+        assert_eq!(grid.column(0).count(), 0);
+        assert_eq!(grid.iter().count(), 0);
+    }
+}
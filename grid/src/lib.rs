@@ -0,0 +1,7 @@
+mod coord;
+mod grid;
+mod neighbourhood;
+
+pub use coord::Coord;
+pub use grid::Grid;
+pub use neighbourhood::{Adjacency, Neighbourhood, MAX_RADIUS};
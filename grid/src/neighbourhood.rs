@@ -0,0 +1,129 @@
+use std::str::FromStr;
+
+/// The offsets of the 4 orthogonal neighbours of a cell: T, L, R, B.
+pub(crate) const ORTHOGONAL_OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// The offsets of all 8 neighbours of a cell, referenced as follows:
+/// TL T TR
+///  L C R
+/// BL B BR
+pub(crate) const ALL_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1), // TL
+    (-1, 0),  // T
+    (-1, 1),  // TR
+    (0, -1),  // L
+    (0, 1),   // R
+    (1, -1),  // BL
+    (1, 0),   // B
+    (1, 1),   // BR
+];
+
+/// The largest radius [`Adjacency`] parses, as a neighbourhood has `(2 * radius + 1)² - 1` cells.
+pub const MAX_RADIUS: usize = 100;
+
+/// Which cells around a cell count as its neighbours.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Adjacency {
+    /// The 4 orthogonally adjacent cells.
+    VonNeumann,
+    /// The 8 orthogonally and diagonally adjacent cells.
+    #[default]
+    Moore,
+    /// Every cell within the given number of rows and columns, e.g. 24 cells for a radius of 2.
+    MooreRadius(usize),
+}
+
+/// A rule for finding the neighbours of a cell in a [`crate::Grid`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Neighbourhood {
+    pub adjacency: Adjacency,
+    /// Whether neighbours past an edge of the grid wrap around to the opposite edge.
+    pub wrap: bool,
+}
+
+impl Neighbourhood {
+    pub fn new(adjacency: Adjacency, wrap: bool) -> Self {
+        Self { adjacency, wrap }
+    }
+
+    /// Returns the row and column offsets of each neighbour, excluding the cell itself.
+    /// Radius neighbourhoods are generated as they are iterated, rather than stored.
+    pub fn offsets(&self) -> impl Iterator<Item = (isize, isize)> + Clone {
+        let (fixed, radius): (&'static [(isize, isize)], isize) = match self.adjacency {
+            Adjacency::VonNeumann => (&ORTHOGONAL_OFFSETS, 0),
+            Adjacency::Moore => (&ALL_OFFSETS, 0),
+            Adjacency::MooreRadius(radius) => (&[], radius as isize),
+        };
+
+        let around = (-radius..=radius)
+            .flat_map(move |rows| (-radius..=radius).map(move |columns| (rows, columns)))
+            .filter(|offset| *offset != (0, 0));
+        fixed.iter().copied().chain(around)
+    }
+
+    /// Returns the furthest number of rows or columns between a cell and its neighbours.
+    pub fn radius(&self) -> usize {
+        match self.adjacency {
+            Adjacency::VonNeumann | Adjacency::Moore => 1,
+            Adjacency::MooreRadius(radius) => radius,
+        }
+    }
+}
+
+impl FromStr for Adjacency {
+    type Err = ();
+
+    /// Parses `von-neumann`, `moore`, or `moore:<radius>` up to [`MAX_RADIUS`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "von-neumann" => Ok(Adjacency::VonNeumann),
+            "moore" => Ok(Adjacency::Moore),
+            _ => {
+                let radius = s.strip_prefix("moore:").ok_or(())?;
+                match radius.parse() {
+                    Ok(radius) if radius <= MAX_RADIUS => Ok(Adjacency::MooreRadius(radius)),
+                    _ => Err(()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod neighbourhood_tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets() {
+        let offsets = |adjacency| {
+            Neighbourhood::new(adjacency, false)
+                .offsets()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(offsets(Adjacency::VonNeumann).len(), 4);
+        assert_eq!(offsets(Adjacency::Moore).len(), 8);
+        assert_eq!(
+            offsets(Adjacency::MooreRadius(1)),
+            offsets(Adjacency::Moore)
+        );
+        assert_eq!(offsets(Adjacency::MooreRadius(2)).len(), 24);
+        assert!(offsets(Adjacency::MooreRadius(0)).is_empty());
+
+        // Huge radii are only iterated as far as needed
+        let huge = Neighbourhood::new(Adjacency::MooreRadius(1 << 20), false);
+        assert_eq!(huge.offsets().nth(1), Some((-(1 << 20), -(1 << 20) + 1)));
+        assert_eq!(huge.radius(), 1 << 20);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("von-neumann".parse(), Ok(Adjacency::VonNeumann));
+        assert_eq!("moore".parse(), Ok(Adjacency::Moore));
+        assert_eq!("moore:3".parse(), Ok(Adjacency::MooreRadius(3)));
+        assert_eq!("moore:".parse::<Adjacency>(), Err(()));
+        assert_eq!("moore:100".parse(), Ok(Adjacency::MooreRadius(MAX_RADIUS)));
+        assert_eq!("moore:100000".parse::<Adjacency>(), Err(()));
+        assert_eq!("hex".parse::<Adjacency>(), Err(()));
+    }
+}
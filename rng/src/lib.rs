@@ -0,0 +1,66 @@
+/// A small SplitMix64 generator, so that generated puzzle inputs are reproducible from their seed alone.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `low..=high`.
+    ///
+    /// # Panics
+    /// Panics if `low` is greater than `high`, or the range is every `u64`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.next_u64() % (high - low + 1)
+    }
+
+    /// Returns true with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+#[cfg(test)]
+mod rng_tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let values = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..4).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(1), values(1));
+        assert_ne!(values(1), values(2));
+        assert_eq!(Rng::new(0).next_u64(), 0xE220_A839_7B1D_CDAF);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut rng = Rng::new(7);
+
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&rng.range(3, 5)));
+        }
+        assert_eq!(rng.range(4, 4), 4);
+    }
+
+    #[test]
+    fn test_chance() {
+        let mut rng = Rng::new(7);
+
+        assert!((0..1000).all(|_| rng.chance(1.0)));
+        assert!((0..1000).all(|_| !rng.chance(0.0)));
+    }
+}